@@ -0,0 +1,128 @@
+//Times cloning, hashing and comparing positions with the compact board, against a synthetic stand-in for the old
+//Vec based layout. The old Game couldn't be cloned, hashed or compared at all, so it can't be timed itself - the
+//stand-in has its fields and derives what the operations need. Its numbers are marked synthetic
+//Run with: cargo bench --bench board
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::hint::black_box;
+use std::time::Instant;
+use solitare_solver::solitare::apply::apply;
+use solitare_solver::solitare::card::Card;
+use solitare_solver::solitare::solitare::Game;
+use solitare_solver::solver::search::Searcher;
+use solitare_solver::solver::transposition::PositionKey;
+
+const POSITIONS: usize = 1_000;
+const ROUNDS: usize = 100;
+
+//The fields Game had before cards were packed into a byte and stacks into fixed arrays, with the derives it lacked
+mod legacy {
+    use solitare_solver::solitare::card::{Card, Suit};
+    use solitare_solver::solitare::solitare::Game;
+
+    #[derive(Clone, Eq, PartialEq, Hash)]
+    pub struct LegacyCard {
+        pub suit: Suit,
+        pub suit_index: i8,
+        pub number: i8,
+        pub next_card: Option<Box<LegacyCard>>,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Hash)]
+    pub struct LegacyTableStack {
+        pub downturned: Vec<LegacyCard>,
+        pub upturned: Vec<LegacyCard>,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Hash)]
+    pub struct LegacyGame {
+        pub table: Vec<LegacyTableStack>,
+        pub aces: Vec<Vec<LegacyCard>>,
+        pub draw: Vec<LegacyCard>,
+        pub waste: Vec<LegacyCard>,
+    }
+
+    fn card(card: &Card) -> LegacyCard {
+        LegacyCard { suit: card.suit(), suit_index: card.suit_index(), number: card.number(), next_card: None }
+    }
+
+    fn cards(cards: &[Card]) -> Vec<LegacyCard> {
+        cards.iter().map(card).collect()
+    }
+
+    impl LegacyGame {
+        pub fn new(game: &Game) -> Self {
+            Self {
+                table: game.table.iter().map(|table_stack| LegacyTableStack {
+                    downturned: cards(&table_stack.downturned),
+                    upturned: cards(&table_stack.upturned),
+                }).collect(),
+                aces: game.aces.iter().map(|ace_stack| cards(&ace_stack.ace_stack)).collect(),
+                draw: cards(&game.draw),
+                waste: cards(&game.waste),
+            }
+        }
+    }
+}
+
+//Positions part way through games, so stacks have a mix of lengths
+fn positions() -> Vec<Game> {
+    (0..POSITIONS as u64).map(|seed| {
+        let mut game = Game::from_seed(seed);
+        for _ in 0..(seed % 20) {
+            let game_state = game.get_game_state();
+            let game_move = match game_state.get_all_moves_youch().first() {
+                Some(&game_move) => game_move.clone(),
+                None => break,
+            };
+            apply(&mut game, &game_move).expect("Generated moves are always legal");
+        }
+        game
+    }).collect()
+}
+
+fn hash<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+//Runs f over every position ROUNDS times and prints the time taken per position
+fn bench<T>(name: &str, positions: &[T], mut f: impl FnMut(&T)) {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for position in positions {
+            f(black_box(position));
+        }
+    }
+    let nanos = start.elapsed().as_nanos() as f64 / (ROUNDS * positions.len()) as f64;
+    println!("{:<32} {:>10.1} ns", name, nanos);
+}
+
+fn main() {
+    let games = positions();
+    let legacy_games: Vec<legacy::LegacyGame> = games.iter().map(legacy::LegacyGame::new).collect();
+
+    println!("Game is {} bytes, synthetic Vec based game is {} bytes plus heap", std::mem::size_of::<Game>(), std::mem::size_of::<legacy::LegacyGame>());
+    println!("Card is {} byte, synthetic Vec based card is {} bytes", std::mem::size_of::<Card>(), std::mem::size_of::<legacy::LegacyCard>());
+
+    bench("clone", &games, |game| { black_box(*game); });
+    bench("clone (synthetic Vec based)", &legacy_games, |game| { black_box(game.clone()); });
+
+    bench("hash", &games, |game| { black_box(hash(game)); });
+    bench("hash (synthetic Vec based)", &legacy_games, |game| { black_box(hash(game)); });
+
+    bench("compare", &games, |game| { black_box(*game == games[0]); });
+    bench("compare (synthetic Vec based)", &legacy_games, |game| { black_box(*game == legacy_games[0]); });
+
+    bench("canonical key", &games, |game| { black_box(PositionKey::new(game)); });
+
+    let start = Instant::now();
+    let mut nodes = 0;
+    for seed in 0..20 {
+        let mut searcher = Searcher::new(20_000);
+        black_box(searcher.search(&Game::from_seed(seed)));
+        nodes += searcher.nodes;
+    }
+    println!("{:<32} {:>10.1} ns", "search per node", start.elapsed().as_nanos() as f64 / nodes as f64);
+}
@@ -0,0 +1,241 @@
+use std::fmt::{Display, Formatter};
+use solitare_solver::solitare::render::RenderStyle;
+use solitare_solver::solitare::rules::Rules;
+use solitare_solver::solver::strategy::StrategyKind;
+use solitare_solver::solver::{pool, SolverKind};
+
+pub const USAGE: &str = "\
+usage: solitare_solver <command> [options]
+
+commands:
+  solve [--seed N | --deck FILE]       solve one deal, a random one by default
+                                       FILE holds 52 card indices, a layout, Solvitaire JSON
+                                       or a PySolFC board - dealt positions only. Seeds are this
+                                       solver's own, not PySolFC game numbers: have PySolFC write
+                                       the board out instead
+  batch --count N [--start S] [--report FILE]
+                                       solve the deals seeded S..S+N, writing a per-deal
+                                       report to FILE as JSON if it ends .json, otherwise CSV
+  replay FILE                          replay a recorded game, checking every move is legal
+                                       and that they win
+  hint [FILE | --seed N | --deck FILE] suggest the next move of a deal, after any moves recorded in FILE
+  play [FILE | --seed N | --deck FILE] play a deal yourself, typing moves - help lists the commands
+  convert [--seed N | --deck FILE] --to layout|solvitaire|pysol|deck
+                                       write a position out in another format - FILE can be
+                                       part way through a game, except to deck
+
+options:
+  --solver greedy|search|parallel|closed
+                                       solver strategy (default search)
+  --strategy greedy|scored             move ranking the greedy solver plays by (default greedy) -
+                                       scored also orders the moves search tries
+  --weights NAME=N,...                 weights of the scored strategy, implying it: reveal, hidden,
+                                       king_space, follow_up, safe_foundation, foundation,
+                                       foundation_down, from_stock, pair_blocked, stock
+  --draw open|one|three                draw mode (default three)
+  --redeals unlimited|N                times the waste may be turned back over (default unlimited)
+  --nodes N                            search budget in positions (default 500000),
+                                       per subtree for the parallel solver, and per sampled
+                                       deal for the closed solver (default 2000)
+  --threads T                          threads for batch deals, or for the parallel solver, which
+                                       a batch gives one deal at a time (default every core) -
+                                       results don't depend on it
+  --format text|json                   output format (default text)
+  --show plain|unicode|color           draw the board after every move of a solve or replay,
+                                       as # comments so the transcript still replays - and
+                                       the style play draws in (default unicode)
+
+A won game prints as a record that replay reads back. Set SOLVER_LOG to error, warn, info, debug or trace for diagnostics.";
+
+pub struct Cli {
+    pub command: Command,
+    pub options: Options,
+}
+
+pub enum Command {
+    Solve(DealSource),
+    Batch { count: u64, start: u64, report: Option<String> },
+    Replay(String),
+    Hint(DealSource),
+    Play(DealSource),
+    Convert(DealSource, Interchange),
+    Help,
+}
+
+//Where the game to play comes from
+pub enum DealSource {
+    Random,
+    Seed(u64),
+    Deck(String), //A file of 52 card indices
+    Record(String), //A recorded game, continued from after its moves
+}
+
+pub struct Options {
+    pub solver: SolverKind,
+    pub strategy: StrategyKind,
+    pub rules: Rules,
+    pub max_nodes: usize,
+    pub threads: usize,
+    pub format: Format,
+    pub show: Option<RenderStyle>, //Draw the board after every move, in this style
+}
+
+//Formats positions can be converted to
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Interchange {
+    Layout,
+    Solvitaire,
+    Pysol,
+    Deck, //Card indices in the order Game::from_deck deals them
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    NoCommand,
+    UnknownCommand(String),
+    UnknownOption(String),
+    MissingValue(String), //The option given without its value
+    InvalidValue(String, String), //The option, and what's wrong with its value
+    UnexpectedArgument(String),
+    MissingArgument(&'static str),
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::NoCommand => write!(f, "no command given"),
+            CliError::UnknownCommand(command) => write!(f, "unknown command '{}'", command),
+            CliError::UnknownOption(option) => write!(f, "unknown option '{}'", option),
+            CliError::MissingValue(option) => write!(f, "{} needs a value", option),
+            CliError::InvalidValue(option, message) => write!(f, "{}: {}", option, message),
+            CliError::UnexpectedArgument(argument) => write!(f, "unexpected argument '{}'", argument),
+            CliError::MissingArgument(argument) => write!(f, "missing {}", argument),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+pub fn parse(args: &[String]) -> Result<Cli, CliError> {
+    let mut options = Options {
+        solver: SolverKind::Search,
+        strategy: StrategyKind::default(),
+        rules: Rules::default(),
+        max_nodes: SolverKind::Search.default_max_nodes(),
+        threads: pool::default_threads(),
+        format: Format::Text,
+        show: None,
+    };
+
+    let (command, args) = match args.split_first() {
+        Some((command, args)) => (command.as_str(), args),
+        None => return Err(CliError::NoCommand),
+    };
+    if command == "help" || command == "--help" || command == "-h" {
+        return Ok(Cli { command: Command::Help, options });
+    }
+    if !["solve", "batch", "replay", "hint", "play", "convert"].contains(&command) {
+        return Err(CliError::UnknownCommand(command.to_string()));
+    }
+
+    let mut seed = None;
+    let mut deck = None;
+    let mut file = None;
+    let mut count = None;
+    let mut start = 0;
+    let mut report = None;
+    let mut to = None;
+    let mut weights = None;
+    let mut max_nodes = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            if file.is_some() {
+                return Err(CliError::UnexpectedArgument(arg.clone()));
+            }
+            file = Some(arg.clone());
+            continue
+        }
+
+        let value = args.next().ok_or_else(|| CliError::MissingValue(arg.clone()))?;
+        let invalid = |message: String| CliError::InvalidValue(arg.clone(), message);
+        let number = |value: &str| value.parse::<u64>().map_err(|_| invalid(format!("'{}' is not a number", value)));
+
+        match arg.as_str() {
+            "--seed" => seed = Some(number(value)?),
+            "--deck" => deck = Some(value.clone()),
+            "--count" => count = Some(number(value)?),
+            "--start" => start = number(value)?,
+            "--report" => report = Some(value.clone()),
+            "--to" => to = Some(match value.as_str() {
+                "layout" => Interchange::Layout,
+                "solvitaire" => Interchange::Solvitaire,
+                "pysol" => Interchange::Pysol,
+                "deck" => Interchange::Deck,
+                _ => return Err(invalid(format!("unknown format '{}', expected layout, solvitaire, pysol or deck", value))),
+            }),
+            "--threads" => options.threads = number(value)?.max(1) as usize,
+            "--nodes" => max_nodes = Some(number(value)? as usize),
+            "--solver" => options.solver = value.parse().map_err(invalid)?,
+            "--strategy" => options.strategy = value.parse().map_err(invalid)?,
+            "--weights" => weights = Some(value.parse().map_err(invalid)?),
+            "--draw" => options.rules.draw = value.parse().map_err(invalid)?,
+            "--redeals" => options.rules.redeals = value.parse().map_err(invalid)?,
+            "--format" => options.format = match value.as_str() {
+                "text" => Format::Text,
+                "json" => Format::Json,
+                _ => return Err(invalid(format!("unknown format '{}', expected text or json", value))),
+            },
+            "--show" => options.show = Some(match value.as_str() {
+                "plain" => RenderStyle::PLAIN,
+                "unicode" => RenderStyle::UNICODE,
+                "color" => RenderStyle::COLOR,
+                _ => return Err(invalid(format!("unknown style '{}', expected plain, unicode or color", value))),
+            }),
+            _ => return Err(CliError::UnknownOption(arg.clone())),
+        }
+    }
+
+    if let Some(weights) = weights {
+        options.strategy = StrategyKind::Scored(weights);
+    }
+    options.max_nodes = max_nodes.unwrap_or(options.solver.default_max_nodes());
+
+    let deal_source = match (seed, deck, &file) {
+        (Some(seed), None, None) => DealSource::Seed(seed),
+        (None, Some(deck), None) => DealSource::Deck(deck),
+        (None, None, Some(file)) => DealSource::Record(file.clone()),
+        (None, None, None) => DealSource::Random,
+        _ => return Err(CliError::UnexpectedArgument(String::from("more than one of --seed, --deck and FILE"))),
+    };
+
+    let command = match command {
+        "solve" => match deal_source {
+            DealSource::Record(file) => return Err(CliError::UnexpectedArgument(file)),
+            deal_source => Command::Solve(deal_source),
+        },
+        "batch" => match deal_source {
+            DealSource::Random => Command::Batch { count: count.ok_or(CliError::MissingArgument("--count"))?, start, report },
+            _ => return Err(CliError::UnexpectedArgument(String::from("batch deals its own games, from --start"))),
+        },
+        "replay" => match deal_source {
+            DealSource::Record(file) => Command::Replay(file),
+            _ => return Err(CliError::MissingArgument("the recorded game to replay")),
+        },
+        "convert" => match deal_source {
+            DealSource::Record(file) => return Err(CliError::UnexpectedArgument(file)),
+            deal_source => Command::Convert(deal_source, to.ok_or(CliError::MissingArgument("--to"))?),
+        },
+        "play" => Command::Play(deal_source),
+        _ => Command::Hint(deal_source),
+    };
+
+    Ok(Cli { command, options })
+}
@@ -0,0 +1,186 @@
+//Just enough JSON for the reports and interchange formats, without pulling in a serialization crate
+
+//A JSON string literal, quoted and escaped
+pub fn string(s: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => escaped += "\\\"",
+            '\\' => escaped += "\\\\",
+            '\n' => escaped += "\\n",
+            c if (c as u32) < 0x20 => escaped += &format!("\\u{:04x}", c as u32),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+pub fn optional_string(s: Option<&str>) -> String {
+    s.map(string).unwrap_or(String::from("null"))
+}
+
+
+//Just enough JSON reading for the interchange formats
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>), //In the order written
+}
+
+impl Value {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(entries) => entries.iter().find(|(entry_key, _)| entry_key == key).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+pub fn parse(s: &str) -> Result<Value, String> {
+    let mut parser = Parser { chars: s.chars().collect(), position: 0 };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.position != parser.chars.len() {
+        return Err(format!("unexpected '{}' after the JSON value", parser.chars[parser.position]));
+    }
+    Ok(value)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn skip_whitespace(&mut self) {
+        while self.chars.get(self.position).is_some_and(|c| c.is_whitespace()) {
+            self.position += 1;
+        }
+    }
+
+    fn next(&mut self) -> Result<char, String> {
+        let c = *self.chars.get(self.position).ok_or("unexpected end of JSON")?;
+        self.position += 1;
+        Ok(c)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.next()? {
+            c if c == expected => Ok(()),
+            c => Err(format!("expected '{}', found '{}'", expected, c)),
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: Value) -> Result<Value, String> {
+        for expected in word.chars() {
+            if self.next()? != expected {
+                return Err(format!("bad literal, expected {}", word));
+            }
+        }
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip_whitespace();
+        match self.chars.get(self.position).copied().ok_or("unexpected end of JSON")? {
+            '{' => {
+                self.position += 1;
+                let mut entries = Vec::new();
+                self.skip_whitespace();
+                if self.chars.get(self.position) == Some(&'}') {
+                    self.position += 1;
+                    return Ok(Value::Object(entries));
+                }
+                loop {
+                    self.expect('"')?;
+                    let key = self.string()?;
+                    self.expect(':')?;
+                    entries.push((key, self.value()?));
+                    self.skip_whitespace();
+                    match self.next()? {
+                        ',' => continue,
+                        '}' => return Ok(Value::Object(entries)),
+                        c => return Err(format!("expected ',' or '}}', found '{}'", c)),
+                    }
+                }
+            },
+            '[' => {
+                self.position += 1;
+                let mut values = Vec::new();
+                self.skip_whitespace();
+                if self.chars.get(self.position) == Some(&']') {
+                    self.position += 1;
+                    return Ok(Value::Array(values));
+                }
+                loop {
+                    values.push(self.value()?);
+                    self.skip_whitespace();
+                    match self.next()? {
+                        ',' => continue,
+                        ']' => return Ok(Value::Array(values)),
+                        c => return Err(format!("expected ',' or ']', found '{}'", c)),
+                    }
+                }
+            },
+            '"' => {
+                self.position += 1;
+                Ok(Value::String(self.string()?))
+            },
+            't' => self.literal("true", Value::Bool(true)),
+            'f' => self.literal("false", Value::Bool(false)),
+            'n' => self.literal("null", Value::Null),
+            _ => {
+                let start = self.position;
+                while self.chars.get(self.position).is_some_and(|c| c.is_ascii_digit() || "+-.eE".contains(*c)) {
+                    self.position += 1;
+                }
+                let number: String = self.chars[start..self.position].iter().collect();
+                number.parse().map(Value::Number).map_err(|_| format!("bad JSON value '{}'", number))
+            },
+        }
+    }
+
+    //After the opening quote
+    fn string(&mut self) -> Result<String, String> {
+        let mut s = String::new();
+        loop {
+            match self.next()? {
+                '"' => return Ok(s),
+                '\\' => match self.next()? {
+                    'n' => s.push('\n'),
+                    't' => s.push('\t'),
+                    'r' => s.push('\r'),
+                    'b' => s.push('\u{8}'),
+                    'f' => s.push('\u{c}'),
+                    'u' => {
+                        let hex: String = (0..4).map(|_| self.next()).collect::<Result<_, _>>()?;
+                        let code = u32::from_str_radix(&hex, 16).map_err(|_| format!("bad escape \\u{}", hex))?;
+                        s.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                    },
+                    c => s.push(c),
+                },
+                c => s.push(c),
+            }
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod json;
+pub mod solitare;
+pub mod solver;
@@ -1,11 +1,309 @@
-mod solver;
-mod solitare;
+mod cli;
+mod play;
 
-use crate::solitare::solitare::{Game};
-use crate::solver::solve;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::process::exit;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use rand::{thread_rng, Rng};
+use solitare_solver::solitare::apply::apply;
+use solitare_solver::solitare::pysol::{from_pysol, to_pysol};
+use solitare_solver::solitare::record::{parse_deck, Deal, GameRecord};
+use solitare_solver::solitare::render::RenderStyle;
+use solitare_solver::solitare::solitare::Game;
+use solitare_solver::solitare::solvitaire::{from_solvitaire, to_solvitaire};
+use solitare_solver::solitare::state::GameMove;
+use solitare_solver::solitare::view::GameView;
+use solitare_solver::solver::closed::ClosedSolver;
+use solitare_solver::json;
+use solitare_solver::solver::batch::Batch;
+use solitare_solver::solver::verify::verify;
+use solitare_solver::solver::{SolveResult, SolverKind};
+use crate::cli::{Command, DealSource, Format, Interchange, Options};
+
+//Prints log records to stderr - verbosity comes from SOLVER_LOG (error, warn, info, debug, trace)
+struct StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{}] {}", record.level(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
 
 fn main() {
-    let game = Box::new(Game::new());
+    let level = std::env::var("SOLVER_LOG").ok()
+        .and_then(|level| level.parse::<Level>().ok())
+        .map(|level| level.to_level_filter())
+        .unwrap_or(LevelFilter::Warn);
+    log::set_logger(&StderrLogger).expect("Logger is only set once");
+    log::set_max_level(level);
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let cli = match cli::parse(&args) {
+        Ok(cli) => cli,
+        Err(cli_error) => {
+            eprintln!("error: {}\n\n{}", cli_error, cli::USAGE);
+            exit(2)
+        },
+    };
+
+    let outcome = match cli.command {
+        Command::Solve(deal_source) => run_solve(deal_source, &cli.options),
+        Command::Batch { count, start, report } => run_batch(start, count, report, &cli.options),
+        Command::Replay(file) => run_replay(&file, &cli.options),
+        Command::Hint(deal_source) => run_hint(deal_source, &cli.options),
+        Command::Play(deal_source) => run_play(deal_source, &cli.options),
+        Command::Convert(deal_source, interchange) => run_convert(deal_source, interchange, &cli.options),
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            Ok(())
+        },
+    };
+
+    if let Err(message) = outcome {
+        eprintln!("error: {}", message);
+        exit(1)
+    }
+}
+
+//The record to play from - a random deal is given a seed so it can always be replayed
+fn load_record(deal_source: DealSource, options: &Options) -> Result<GameRecord, String> {
+    match deal_source {
+        DealSource::Random => Ok(GameRecord::new(Deal::Seed(thread_rng().gen()), options.rules)),
+        DealSource::Seed(seed) => Ok(GameRecord::new(Deal::Seed(seed), options.rules)),
+        DealSource::Deck(file) => {
+            let deck_integers = load_position(&file)?.deal_order()
+                .ok_or_else(|| format!("{}: not a freshly dealt position, so it can't be replayed from a deck", file))?;
+            Ok(GameRecord::new(Deal::Deck(deck_integers), options.rules))
+        },
+        DealSource::Record(file) => read_file(&file)?.parse().map_err(|record_error| format!("{}: {}", file, record_error)),
+    }
+}
+
+//Reads a position in any of the formats convert writes, telling them apart by their look
+fn load_position(file: &str) -> Result<Game, String> {
+    let text = read_file(file)?;
+    let trimmed = text.trim_start();
+
+    let game = if trimmed.starts_with('{') {
+        from_solvitaire(&text)
+    } else if text.contains("Talon:") || text.contains("Foundations:") {
+        from_pysol(&text)
+    } else if text.contains("foundation") || text.lines().any(|line| line.trim_start().starts_with("1:")) {
+        text.parse::<Game>().map_err(|layout_error| layout_error.to_string())
+    } else {
+        parse_deck(&text).and_then(|deck_integers| Game::from_deck(&deck_integers).map_err(|deck_error| deck_error.to_string()))
+    };
+
+    game.map_err(|message| format!("{}: {}", file, message))
+}
+
+fn read_file(file: &str) -> Result<String, String> {
+    std::fs::read_to_string(file).map_err(|io_error| format!("{}: {}", file, io_error))
+}
+
+//Plays the record's moves from its start, stopping at the first illegal one
+fn play_record(record: &GameRecord) -> Result<Game, String> {
+    let mut game = record.start().map_err(|deck_error| deck_error.to_string())?;
+
+    for (move_index, game_move) in record.moves.iter().enumerate() {
+        apply(&mut game, game_move)
+            .map_err(|move_error| format!("move {} ({}) is illegal: {}", move_index + 1, game_move, move_error))?;
+    }
+
+    Ok(game)
+}
+
+fn run_solve(deal_source: DealSource, options: &Options) -> Result<(), String> {
+    let mut record = load_record(deal_source, options)?;
+    let game = record.start().map_err(|deck_error| deck_error.to_string())?;
+
+    let result = options.solver.solve_counting_with(&game, options.max_nodes, options.strategy, options.threads).0;
+
+    match options.format {
+        Format::Text => {
+            println!("# {}", describe_result(&result));
+            if let SolveResult::Won(moves) = result {
+                record.moves = moves;
+            }
+            match options.show {
+                Some(style) => print_transcript(&record, game, style),
+                None => print!("{}", record),
+            }
+        },
+        Format::Json => println!("{}", result_json(&record, options.solver, &result)),
+    }
+
+    Ok(())
+}
+
+fn run_batch(start: u64, count: u64, report: Option<String>, options: &Options) -> Result<(), String> {
+    let mut batch = Batch::new(options.solver, options.rules, options.max_nodes);
+    batch.threads = options.threads;
+    batch.strategy = options.strategy;
+    let batch_report = batch.run(start..start + count);
+
+    if let Some(file) = report {
+        let mut writer = BufWriter::new(File::create(&file).map_err(|io_error| format!("{}: {}", file, io_error))?);
+        let written = if file.ends_with(".json") { batch_report.write_json(&mut writer) } else { batch_report.write_csv(&mut writer) };
+        written.and_then(|_| writer.flush()).map_err(|io_error| format!("{}: {}", file, io_error))?;
+    }
+
+    match options.format {
+        Format::Text => {
+            for deal in &batch_report.deals {
+                match &deal.reason {
+                    Some(reason) => println!("seed {}: {:?} ({})", deal.seed, deal.outcome, reason),
+                    None => println!("seed {}: {:?} in {} moves", deal.seed, deal.outcome, deal.moves),
+                }
+            }
+
+            let summary = batch_report.summary();
+            println!("won {} of {} ({:.1}%), lost {}, stuck {}", summary.won, summary.deals, summary.win_rate() * 100.0, summary.lost, summary.stuck);
+            println!("average {:.1} moves per win, {:.0} positions searched per deal", summary.average_moves, summary.average_nodes);
+            println!("{:.2}s solving, {:.2}s wall time", summary.solve_time.as_secs_f64(), summary.elapsed.as_secs_f64());
+        },
+        Format::Json => batch_report.write_json(&mut std::io::stdout()).map_err(|io_error| io_error.to_string())?,
+    }
+
+    Ok(())
+}
+
+fn run_replay(file: &str, options: &Options) -> Result<(), String> {
+    let record: GameRecord = read_file(file)?.parse().map_err(|record_error| format!("{}: {}", file, record_error))?;
+    let game = record.start().map_err(|deck_error| deck_error.to_string())?;
+
+    if let (Format::Text, Some(style)) = (options.format, options.show) {
+        print_transcript(&record, game, style);
+    }
+
+    let verified = verify(&game, &record.moves);
+    match (options.format, &verified) {
+        (Format::Text, Ok(verified)) => {
+            println!("verified: {} moves win the game", verified.moves);
+            if !verified.ungenerated.is_empty() {
+                println!("legal moves move generation didn't offer, at steps {:?}", verified.ungenerated);
+            }
+        },
+        (Format::Text, Err(verify_error)) => println!("not verified: {}", verify_error),
+        (Format::Json, Ok(verified)) => println!("{{\"verified\": true, \"moves\": {}, \"ungenerated\": {:?}}}", verified.moves, verified.ungenerated),
+        (Format::Json, Err(verify_error)) => println!("{{\"verified\": false, \"error\": {}}}", json::string(&verify_error.to_string())),
+    }
+
+    verified.map(|_| ()).map_err(|_| format!("{} is not a winning solution", file))
+}
+
+fn run_hint(deal_source: DealSource, options: &Options) -> Result<(), String> {
+    let record = load_record(deal_source, options)?;
+    let game = play_record(&record)?;
+    let hint = find_hint(&game, options);
+
+    match (options.format, hint) {
+        (Format::Text, Some(game_move)) => println!("{}", game_move),
+        (Format::Text, None) => println!("no hint"),
+        (Format::Json, hint) => println!("{{\"hint\": {}}}", json::optional_string(hint.map(|game_move| game_move.to_string()).as_deref())),
+    }
+
+    Ok(())
+}
+
+fn run_play(deal_source: DealSource, options: &Options) -> Result<(), String> {
+    let record = load_record(deal_source, options)?;
+    let game = play_record(&record)?;
+
+    play::play(record, game, options).map_err(|io_error| io_error.to_string())
+}
+
+//The first move of the solver's winning line from the game
+fn find_hint(game: &Game, options: &Options) -> Option<GameMove> {
+    match options.solver {
+        //Only hints from what a player can see - the cards they watched go by in the stock are forgotten
+        SolverKind::Closed => {
+            let mut closed_solver = ClosedSolver::new(0);
+            closed_solver.max_nodes = options.max_nodes;
+            closed_solver.hint(&GameView::new(game))
+        },
+        solver => match solver.solve_counting_with(game, options.max_nodes, options.strategy, options.threads).0 {
+            SolveResult::Won(moves) => moves.into_iter().next(),
+            _ => None,
+        },
+    }
+}
+
+fn run_convert(deal_source: DealSource, interchange: Interchange, options: &Options) -> Result<(), String> {
+    let game = match deal_source {
+        DealSource::Deck(file) => load_position(&file)?,
+        deal_source => load_record(deal_source, options)?.start().map_err(|deck_error| deck_error.to_string())?,
+    };
+
+    match interchange {
+        Interchange::Layout => print!("{}", game),
+        Interchange::Solvitaire => print!("{}", to_solvitaire(&game)),
+        Interchange::Pysol => print!("{}", to_pysol(&game)),
+        Interchange::Deck => {
+            let deck_integers = game.deal_order().ok_or("not a freshly dealt position, so it has no deck")?;
+            println!("{}", deck_integers.map(|card_index| card_index.to_string()).join(" "));
+        },
+    }
+
+    Ok(())
+}
+
+//The record with the board drawn before its first move and after each one, as # comments so replay
+//still reads it. Stops drawing at an illegal move, leaving the rest for replay to report
+fn print_transcript(record: &GameRecord, mut game: Game, style: RenderStyle) {
+    let print_board = |game: &Game| {
+        for line in game.board(style).to_string().lines() {
+            println!("# {}", line);
+        }
+    };
+
+    print!("{}", GameRecord { moves: Vec::new(), ..record.clone() });
+    print_board(&game);
+
+    let mut legal = true;
+    for game_move in &record.moves {
+        println!("{}", game_move);
+        legal = legal && apply(&mut game, game_move).is_ok();
+        if legal {
+            print_board(&game);
+        }
+    }
+}
+
+fn describe_result(result: &SolveResult) -> String {
+    match result {
+        SolveResult::Won(moves) => format!("WON in {} moves", moves.len()),
+        SolveResult::Lost => String::from("LOST"),
+        SolveResult::Stuck(reason) => format!("STUCK: {}", reason),
+        SolveResult::BudgetExceeded => String::from("BUDGET EXCEEDED"),
+    }
+}
+
+fn result_json(record: &GameRecord, solver: SolverKind, result: &SolveResult) -> String {
+    let deal = match &record.deal {
+        Deal::Seed(seed) => format!("\"seed\": {}", seed),
+        Deal::Deck(deck_integers) => format!("\"deck\": {:?}", deck_integers),
+    };
+    let (outcome, reason, moves) = match result {
+        SolveResult::Won(moves) => ("won", None, moves.iter().map(|game_move| json::string(&game_move.to_string())).collect::<Vec<_>>()),
+        SolveResult::Lost => ("lost", None, Vec::new()),
+        SolveResult::Stuck(reason) => ("stuck", Some(reason), Vec::new()),
+        SolveResult::BudgetExceeded => ("budget_exceeded", None, Vec::new()),
+    };
 
-    solve(game);
-}
\ No newline at end of file
+    format!(
+        "{{{}, \"draw\": \"{}\", \"redeals\": \"{}\", \"solver\": \"{}\", \"result\": \"{}\", \"reason\": {}, \"moves\": [{}]}}",
+        deal, record.rules.draw, record.rules.redeals, solver, outcome,
+        json::optional_string(reason.map(String::as_str)),
+        moves.join(", ")
+    )
+}
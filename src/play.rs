@@ -0,0 +1,97 @@
+use std::io::{BufRead, Write};
+use solitare_solver::solitare::apply::{apply, AppliedMove};
+use solitare_solver::solitare::record::GameRecord;
+use solitare_solver::solitare::render::RenderStyle;
+use solitare_solver::solitare::shorthand::resolve_move;
+use solitare_solver::solitare::solitare::Game;
+use solitare_solver::solitare::state::GameFinalState;
+use solitare_solver::solver::greedy::explain_move;
+use crate::cli::Options;
+
+const PLAY_HELP: &str = "\
+moves are typed in notation, or as much of it as it takes to tell the move apart:
+  t3-t5  t3-f  w-t2  s12-f  fH-t4  flip 6  draw  recycle  t3.2-t5.4
+commands:
+  undo            take back the last move
+  hint            ask the solver for the next move, and why it's a good one
+  moves           every move on offer, and what the greedy rules make of each
+  board           draw the board again
+  save FILE       write the game so far as a record, for replay or hint
+  quit";
+
+//Plays the game from the terminal, a line at a time, until quit or the input runs out
+pub fn play(mut record: GameRecord, mut game: Game, options: &Options) -> std::io::Result<()> {
+    let style = options.show.unwrap_or(RenderStyle::UNICODE);
+    let mut applied_moves: Vec<AppliedMove> = Vec::new(); //Only the moves made this session can be undone
+
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+
+    println!("{}", PLAY_HELP);
+    println!();
+    print!("{}", game.board(style));
+
+    loop {
+        print!("> ");
+        stdout.flush()?;
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            println!();
+            return Ok(())
+        }
+
+        match line.trim() {
+            "" => (),
+            "quit" | "q" | "exit" => return Ok(()),
+            "help" | "?" => println!("{}", PLAY_HELP),
+            "board" | "b" => print!("{}", game.board(style)),
+            "undo" | "u" => match applied_moves.pop() {
+                Some(applied_move) => {
+                    game.undo(&applied_move);
+                    record.moves.pop();
+                    println!("took back {}", applied_move.game_move);
+                    print!("{}", game.board(style));
+                },
+                None => println!("nothing to undo"),
+            },
+            "hint" | "h" => match crate::find_hint(&game, options) {
+                Some(game_move) => println!("{} - {}", game_move, explain_move(&game_move, &game)),
+                None => println!("no hint - the {} solver found no win from here", options.solver),
+            },
+            "moves" | "m" => {
+                let game_state = game.get_game_state();
+                for game_move in game_state.get_all_moves_youch() {
+                    println!("{} - {}", game_move, explain_move(game_move, &game));
+                }
+            },
+            command => {
+                if let Some(file) = command.strip_prefix("save ") {
+                    match std::fs::write(file.trim(), record.to_string()) {
+                        Ok(()) => println!("saved {} moves to {}", record.moves.len(), file.trim()),
+                        Err(io_error) => println!("{}: {}", file.trim(), io_error),
+                    }
+                    continue
+                }
+
+                let applied = resolve_move(&game, command)
+                    .and_then(|game_move| apply(&mut game, &game_move).map_err(|move_error| format!("{} is illegal: {}", game_move, move_error)));
+                match applied {
+                    Ok(applied_move) => {
+                        println!("{}", applied_move.game_move);
+                        record.moves.push(applied_move.game_move.clone());
+                        applied_moves.push(applied_move);
+                        print!("{}", game.board(style));
+
+                        match game.get_game_state().get_final_state() {
+                            GameFinalState::WON => println!("won in {} moves", record.moves.len()),
+                            GameFinalState::LOST => println!("no moves left - undo to try another line"),
+                            GameFinalState::UNFINISHED => (),
+                        }
+                    },
+                    Err(message) => println!("{}", message),
+                }
+            },
+        }
+    }
+}
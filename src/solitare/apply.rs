@@ -0,0 +1,417 @@
+use std::fmt::{Display, Formatter};
+use log::trace;
+use crate::solitare::card::Card;
+use crate::solitare::pile::Pile;
+use crate::solitare::rules::DrawMode;
+use crate::solitare::solitare::Game;
+use crate::solitare::state::{CardPosition, GameMove};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MoveError {
+    StackOutOfBounds(i8), //Table stacks are 0..7
+    SuitOutOfBounds(i8), //Ace stacks are 0..4
+    IndexOutOfBounds(i8), //No card at that index of the stack, deck or draw pile
+    EmptySource, //Nothing to move from the ace stack or waste
+    FlipCovered, //Only the last downturned card of a stack with no upturned cards can be flipped
+    NotDrawable, //DrawDeck cards are only playable in open draw mode, Waste and Stock moves only outside it
+    StockEmpty,
+    StockNotEmpty, //The waste can only be turned back over once the stock runs out
+    NoRedealsLeft,
+    SameStack,
+    DestinationIndex(i8), //Cards can only go on the end of a stack
+    DestinationFaceDown, //Cards can't go onto a stack whose last card is downturned
+    NotAKing, //Only kings can go into a vacant stack
+    SameColor, //Table stacks alternate colour
+    NotDescending, //Table stacks descend by one
+    TooManyCards, //Ace stacks take one card at a time
+    WrongSuit, //Each ace stack holds a single suit
+    OutOfSequence, //Ace stacks ascend by one from the ace
+    IllegalMove, //No such move between these positions
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::StackOutOfBounds(stack_index) => write!(f, "there is no table stack {}", stack_index),
+            MoveError::SuitOutOfBounds(suit_index) => write!(f, "there is no ace stack {}", suit_index),
+            MoveError::IndexOutOfBounds(index) => write!(f, "there is no card at index {}", index),
+            MoveError::EmptySource => write!(f, "there is no card to move"),
+            MoveError::FlipCovered => write!(f, "only an uncovered downturned card can be flipped"),
+            MoveError::NotDrawable => write!(f, "that card can't be played from the draw pile under these rules"),
+            MoveError::StockEmpty => write!(f, "the stock is empty"),
+            MoveError::StockNotEmpty => write!(f, "the waste can only be recycled once the stock is empty"),
+            MoveError::NoRedealsLeft => write!(f, "no redeals left"),
+            MoveError::SameStack => write!(f, "cards can't move onto their own stack"),
+            MoveError::DestinationIndex(index) => write!(f, "cards can only go on the end of a stack, not index {}", index),
+            MoveError::DestinationFaceDown => write!(f, "cards can't go onto a downturned card"),
+            MoveError::NotAKing => write!(f, "only a king can go into a vacant stack"),
+            MoveError::SameColor => write!(f, "table stacks must alternate colour"),
+            MoveError::NotDescending => write!(f, "table stacks must descend by one"),
+            MoveError::TooManyCards => write!(f, "ace stacks take one card at a time"),
+            MoveError::WrongSuit => write!(f, "ace stacks hold a single suit"),
+            MoveError::OutOfSequence => write!(f, "ace stacks must ascend by one from the ace"),
+            MoveError::IllegalMove => write!(f, "no such move"),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+
+//A move that has been applied to a game, with everything needed to undo it
+#[derive(Clone, Debug)]
+pub struct AppliedMove {
+    pub game_move: GameMove,
+    pub cards_moved: usize,
+}
+
+//Checks the move is legal for the game, then makes it. An illegal move leaves the game untouched
+pub fn apply(game: &mut Game, game_move: &GameMove) -> Result<AppliedMove, MoveError> {
+    let cards_moved = validate(game, game_move)?;
+    perform(game, game_move);
+    debug_check_invariants(game, game_move, "making");
+
+    Ok(AppliedMove {
+        game_move: game_move.clone(),
+        cards_moved,
+    })
+}
+
+//Catches a move made wrongly at the move itself, rather than wherever the lost or duplicated card turns up
+fn debug_check_invariants(game: &Game, game_move: &GameMove, doing: &str) {
+    if cfg!(debug_assertions) {
+        if let Err(invariant_error) = game.check_invariants() {
+            panic!("{} {} broke the game: {}", doing, game_move, invariant_error);
+        }
+    }
+}
+
+fn stack_index(stack_index: i8) -> Result<usize, MoveError> {
+    if (0..7).contains(&stack_index) { Ok(stack_index as usize) } else { Err(MoveError::StackOutOfBounds(stack_index)) }
+}
+
+fn suit_index(suit_index: i8) -> Result<usize, MoveError> {
+    if (0..4).contains(&suit_index) { Ok(suit_index as usize) } else { Err(MoveError::SuitOutOfBounds(suit_index)) }
+}
+
+fn card_index(index: i8, len: usize) -> Result<usize, MoveError> {
+    if index >= 0 && (index as usize) < len { Ok(index as usize) } else { Err(MoveError::IndexOutOfBounds(index)) }
+}
+
+//Returns how many cards the move takes
+fn validate(game: &Game, game_move: &GameMove) -> Result<usize, MoveError> {
+    let open_draw = game.rules.draw == DrawMode::Open;
+
+    //Turning the stock over
+    match (&game_move.from, &game_move.to) {
+        (CardPosition::Stock, CardPosition::Waste) => {
+            if open_draw { return Err(MoveError::NotDrawable) }
+            if game.draw.is_empty() { return Err(MoveError::StockEmpty) }
+
+            return Ok(game.rules.draw_count().min(game.draw.len()))
+        },
+        (CardPosition::Waste, CardPosition::Stock) => {
+            if open_draw { return Err(MoveError::NotDrawable) }
+            if !game.draw.is_empty() { return Err(MoveError::StockNotEmpty) }
+            if game.waste.is_empty() { return Err(MoveError::EmptySource) }
+            if !game.rules.can_redeal(game.redeals_used) { return Err(MoveError::NoRedealsLeft) }
+
+            return Ok(game.waste.len())
+        },
+        (CardPosition::TableDownturned { stack_index: from_stack, downturned_index }, CardPosition::TableUpturned { stack_index: to_stack, upturned_index }) => {
+            let stack = &game.table[stack_index(*from_stack)?];
+            card_index(*downturned_index, stack.downturned.len())?;
+
+            if *to_stack != *from_stack || *upturned_index != 0 { return Err(MoveError::IllegalMove) }
+            if *downturned_index as usize != stack.downturned.len() - 1 || !stack.upturned.is_empty() { return Err(MoveError::FlipCovered) }
+
+            return Ok(1)
+        },
+        _ => (),
+    }
+
+    //Fetch the cards moving from 'from'
+    let cards: &[Card] = match game_move.from {
+        CardPosition::TableUpturned { stack_index: from_stack, upturned_index } => {
+            let stack = &game.table[stack_index(from_stack)?];
+            let upturned_index = card_index(upturned_index, stack.upturned.len())?;
+
+            &stack.upturned[upturned_index..]
+        },
+        CardPosition::Ace { suit_index: from_suit } => {
+            let ace_stack = &game.aces[suit_index(from_suit)?];
+
+            match ace_stack.ace_stack.last() {
+                Some(card) => std::slice::from_ref(card),
+                None => return Err(MoveError::EmptySource),
+            }
+        },
+        CardPosition::DrawDeck { deck_index } => {
+            if !open_draw { return Err(MoveError::NotDrawable) }
+
+            std::slice::from_ref(&game.draw[card_index(deck_index, game.draw.len())?])
+        },
+        CardPosition::Waste => {
+            if open_draw { return Err(MoveError::NotDrawable) }
+
+            match game.waste.last() {
+                Some(card) => std::slice::from_ref(card),
+                None => return Err(MoveError::EmptySource),
+            }
+        },
+        CardPosition::TableDownturned { .. } | CardPosition::Stock => return Err(MoveError::IllegalMove),
+    };
+    let card = &cards[0];
+
+    //Check they can go to 'to'
+    match game_move.to {
+        CardPosition::TableUpturned { stack_index: to_stack, upturned_index } => {
+            let stack = &game.table[stack_index(to_stack)?];
+
+            if let CardPosition::TableUpturned { stack_index: from_stack, .. } = game_move.from {
+                if from_stack == to_stack { return Err(MoveError::SameStack) }
+            }
+            if upturned_index < 0 || upturned_index as usize != stack.upturned.len() { return Err(MoveError::DestinationIndex(upturned_index)) }
+
+            match stack.upturned.last() {
+                Some(onto_card) => {
+                    if card.suit().same_color(onto_card.suit()) { return Err(MoveError::SameColor) }
+                    if onto_card.number() != card.number() + 1 { return Err(MoveError::NotDescending) }
+                },
+                None => {
+                    if !stack.downturned.is_empty() { return Err(MoveError::DestinationFaceDown) }
+                    if card.number() != 13 { return Err(MoveError::NotAKing) }
+                },
+            }
+        },
+        CardPosition::Ace { suit_index: to_suit } => {
+            let ace_stack = &game.aces[suit_index(to_suit)?];
+
+            if let CardPosition::Ace { .. } = game_move.from { return Err(MoveError::IllegalMove) }
+            if cards.len() != 1 { return Err(MoveError::TooManyCards) }
+            if card.suit_index() != to_suit { return Err(MoveError::WrongSuit) }
+            if card.number() as usize != ace_stack.ace_stack.len() + 1 { return Err(MoveError::OutOfSequence) }
+        },
+        CardPosition::TableDownturned { .. } | CardPosition::DrawDeck { .. } | CardPosition::Waste | CardPosition::Stock => return Err(MoveError::IllegalMove),
+    }
+
+    Ok(cards.len())
+}
+
+//Makes an already validated move
+fn perform(game: &mut Game, game_move: &GameMove) {
+
+    //Turning the stock over moves many cards and involves neither the table nor the aces
+    match (&game_move.from, &game_move.to) {
+        (CardPosition::Stock, CardPosition::Waste) => {
+            let draw_count = game.rules.draw_count().min(game.draw.len());
+            for _ in 0..draw_count {
+                let card = game.draw.pop().unwrap();
+                game.waste.push(card);
+            }
+            trace!(" - - Drew {:?}", &game.waste[(game.waste.len() - draw_count)..]);
+            return
+        },
+        (CardPosition::Waste, CardPosition::Stock) => {
+            //Turning the waste over puts its bottom card on top of the stock
+            while let Some(card) = game.waste.pop() {
+                game.draw.push(card);
+            }
+            game.redeals_used += 1;
+            trace!(" - - Recycled waste into stock, redeal {}", game.redeals_used);
+            return
+        },
+        _ => (),
+    }
+
+    //Fetch & remove from 'from'
+    let cards: Pile<13> = match game_move.from {
+        CardPosition::TableDownturned { stack_index, downturned_index } => {
+            let stack = &mut game.table[stack_index as usize];
+            Pile::from_slice(&[stack.downturned.remove(downturned_index as usize)])
+        },
+        CardPosition::TableUpturned { stack_index, upturned_index } => {
+            let stack = &mut game.table[stack_index as usize];
+            stack.upturned.split_off(upturned_index as usize)
+        },
+        CardPosition::Ace { suit_index } => {
+            let ace_stack = &mut game.aces[suit_index as usize];
+            Pile::from_slice(&[ace_stack.ace_stack.pop().unwrap()])
+        },
+        CardPosition::DrawDeck { deck_index } => {
+            Pile::from_slice(&[game.draw.remove(deck_index as usize)])
+        },
+        CardPosition::Waste => {
+            Pile::from_slice(&[game.waste.pop().unwrap()])
+        },
+        CardPosition::Stock => unreachable!("Validated moves only leave the Stock by drawing"),
+    };
+    trace!(" - - Executing move {:?}", cards);
+
+    //Add to 'to'
+    match game_move.to {
+        CardPosition::TableUpturned { stack_index, upturned_index } => {
+            let stack = &mut game.table[stack_index as usize];
+
+            stack.upturned.extend_from_slice(&cards);
+
+            if let Some(onto_card) = stack.upturned.get((upturned_index-1) as usize) {
+                trace!(" - - - Onto [{:?}]", onto_card);
+            }
+        },
+        CardPosition::Ace { suit_index } => {
+            //Can only move 1 card to the ace (at a time)
+            game.aces[suit_index as usize].ace_stack.push(cards[0]);
+        },
+        CardPosition::TableDownturned { .. } | CardPosition::DrawDeck { .. } | CardPosition::Waste | CardPosition::Stock => {
+            unreachable!("Validated moves only go to TableUpturned or Ace")
+        },
+    }
+}
+
+
+impl Game {
+    //Restores the game to exactly how it was before the move - records must be undone newest first
+    pub fn undo(&mut self, applied_move: &AppliedMove) {
+        let game_move = &applied_move.game_move;
+        let cards_moved = applied_move.cards_moved;
+
+        //Turning the stock over
+        match (&game_move.from, &game_move.to) {
+            (CardPosition::Stock, CardPosition::Waste) => {
+                for _ in 0..cards_moved {
+                    let card = self.waste.pop().unwrap();
+                    self.draw.push(card);
+                }
+                return
+            },
+            (CardPosition::Waste, CardPosition::Stock) => {
+                while let Some(card) = self.draw.pop() {
+                    self.waste.push(card);
+                }
+                self.redeals_used -= 1;
+                return
+            },
+            _ => (),
+        }
+
+        //Take the cards back from 'to'
+        let cards: Pile<13> = match game_move.to {
+            CardPosition::TableUpturned { stack_index, upturned_index } => {
+                self.table[stack_index as usize].upturned.split_off(upturned_index as usize)
+            },
+            CardPosition::Ace { suit_index } => {
+                Pile::from_slice(&[self.aces[suit_index as usize].ace_stack.pop().unwrap()])
+            },
+            CardPosition::TableDownturned { .. } | CardPosition::DrawDeck { .. } | CardPosition::Waste | CardPosition::Stock => {
+                unreachable!("Applied moves only go to TableUpturned or Ace")
+            },
+        };
+        debug_assert_eq!(cards.len(), cards_moved);
+
+        //Put them back where they came from
+        match game_move.from {
+            CardPosition::TableDownturned { stack_index, .. } => {
+                self.table[stack_index as usize].downturned.extend_from_slice(&cards);
+            },
+            CardPosition::TableUpturned { stack_index, .. } => {
+                self.table[stack_index as usize].upturned.extend_from_slice(&cards);
+            },
+            CardPosition::Ace { suit_index } => {
+                self.aces[suit_index as usize].ace_stack.extend_from_slice(&cards);
+            },
+            CardPosition::DrawDeck { deck_index } => {
+                self.draw.insert(deck_index as usize, cards[0]);
+            },
+            CardPosition::Waste => {
+                self.waste.extend_from_slice(&cards);
+            },
+            CardPosition::Stock => unreachable!("Applied moves only leave the Stock by drawing"),
+        }
+        debug_check_invariants(self, game_move, "undoing");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use crate::solitare::rules::{DrawMode, Redeals};
+    use super::*;
+
+    //Plays random legal moves from a seeded deal, returning the game before each move and what each move applied
+    fn play_randomly(game: &mut Game, seed: u64, max_moves: usize) -> (Vec<Game>, Vec<AppliedMove>) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut games = Vec::new();
+        let mut applied_moves = Vec::new();
+
+        for _ in 0..max_moves {
+            let game_moves: Vec<GameMove> = game.get_game_state().get_all_moves_youch().into_iter().cloned().collect();
+            if game_moves.is_empty() {
+                break
+            }
+            let game_move = &game_moves[rng.gen_range(0..game_moves.len())];
+
+            games.push(*game);
+            applied_moves.push(apply(game, game_move).expect("Generated moves are always legal"));
+        }
+
+        (games, applied_moves)
+    }
+
+    #[test]
+    fn undo_restores_every_position_of_random_games() {
+        for draw in [DrawMode::Open, DrawMode::One, DrawMode::Three] {
+            for seed in 0..40 {
+                let mut game = Game::from_seed(seed);
+                game.rules.draw = draw;
+                let (games, mut applied_moves) = play_randomly(&mut game, seed, 300);
+                assert!(!applied_moves.is_empty());
+
+                for game_before in games.iter().rev() {
+                    let applied_move = applied_moves.pop().unwrap();
+                    game.undo(&applied_move);
+                    assert!(game == *game_before, "draw {} seed {}: undoing {} left\n{}\ninstead of\n{}", draw, seed, applied_move.game_move, game, game_before);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn undo_restores_redeals_used() {
+        let mut game = Game::from_seed(7);
+        game.rules.draw = DrawMode::One;
+        game.rules.redeals = Redeals::Limited(1);
+        let start = game;
+
+        let mut applied_moves = Vec::new();
+        while !game.draw.is_empty() {
+            applied_moves.push(apply(&mut game, &GameMove { from: CardPosition::Stock, to: CardPosition::Waste }).unwrap());
+        }
+        applied_moves.push(apply(&mut game, &GameMove { from: CardPosition::Waste, to: CardPosition::Stock }).unwrap());
+        assert_eq!(game.redeals_used, 1);
+
+        while let Some(applied_move) = applied_moves.pop() {
+            game.undo(&applied_move);
+        }
+        assert!(game == start);
+    }
+
+    #[test]
+    fn illegal_moves_leave_the_game_untouched() {
+        let mut game = Game::from_seed(3);
+        let start = game;
+
+        //Stack 0 holds a single card, and only kings go into vacant stacks
+        let illegal_moves = [
+            (GameMove { from: CardPosition::TableUpturned { stack_index: 7, upturned_index: 0 }, to: CardPosition::Ace { suit_index: 0 } }, MoveError::StackOutOfBounds(7)),
+            (GameMove { from: CardPosition::Waste, to: CardPosition::Stock }, MoveError::StockNotEmpty),
+            (GameMove { from: CardPosition::Ace { suit_index: 1 }, to: CardPosition::TableUpturned { stack_index: 0, upturned_index: 1 } }, MoveError::EmptySource),
+            (GameMove { from: CardPosition::TableUpturned { stack_index: 1, upturned_index: 0 }, to: CardPosition::TableUpturned { stack_index: 1, upturned_index: 1 } }, MoveError::SameStack),
+        ];
+        for (game_move, move_error) in illegal_moves {
+            assert_eq!(apply(&mut game, &game_move).unwrap_err(), move_error, "{}", game_move);
+            assert!(game == start);
+        }
+    }
+}
@@ -1,34 +1,84 @@
-use std::fmt::{Debug, Formatter};
-use std::marker::PhantomData;
-
-#[derive(Clone)] //Not copy.
-pub struct Card<'a> {
-    pub suit: Suit,
-    pub suit_index: i8,
-    pub number: i8,
-    pub next_card: Option<Box<Card<'a>>>,
-    pub _phantom: PhantomData<&'a ()>
-}
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
+
+//A card packed into a single byte - its card index, suit_index * 13 + number - 1
+#[derive(Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct Card(u8);
 
-impl<'d> Card<'d> {
+impl Card {
     pub fn new(card_index: i8) -> Self {
-        Self {
-            suit: Suit::from_index(card_index / 13),
-            suit_index: card_index / 13,
-            number: card_index.rem_euclid(13) + 1,
-            next_card: None,
-            _phantom: PhantomData::default()
-        }
+        debug_assert!((0..52).contains(&card_index));
+        Self(card_index as u8)
+    }
+
+    pub fn index(self) -> usize { self.0 as usize }
+    pub fn suit(self) -> Suit { Suit::from_index(self.suit_index()) }
+    pub fn suit_index(self) -> i8 { (self.0 / 13) as i8 }
+    pub fn number(self) -> i8 { (self.0 % 13) as i8 + 1 }
+}
+
+impl Debug for Card {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}:{}", self.suit(), self.number())
     }
 }
 
-impl<'a> Debug for Card<'a> {
+//Short form - rank then suit, like KH, TD or 5S
+impl Display for Card {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}:{}", self.suit, self.number)
+        let rank = match self.number() {
+            1 => 'A',
+            10 => 'T',
+            11 => 'J',
+            12 => 'Q',
+            13 => 'K',
+            number => (b'0' + number as u8) as char,
+        };
+        write!(f, "{}{}", rank, SUIT_LETTERS[self.suit_index() as usize])
+    }
+}
+
+//Reads the short form, in either case. 10 is accepted for T
+impl FromStr for Card {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad_card = || format!("bad card '{}'", s);
+        if s.len() < 2 || !s.is_ascii() {
+            return Err(bad_card());
+        }
+        let (rank, suit) = s.split_at(s.len() - 1);
+
+        let number: i8 = match rank.to_ascii_uppercase().as_str() {
+            "A" => 1,
+            "T" | "10" => 10,
+            "J" => 11,
+            "Q" => 12,
+            "K" => 13,
+            rank => match rank.parse() {
+                Ok(number @ 2..=9) => number,
+                _ => return Err(bad_card()),
+            },
+        };
+        let suit_index = suit_index_of_letter(suit).ok_or_else(bad_card)? as i8;
+
+        Ok(Card::new(suit_index * 13 + number - 1))
     }
 }
 
 
+//The letter each suit is written as, by suit index - in cards, the layout's foundation line and move notation
+pub const SUIT_LETTERS: [char; 4] = ['H', 'D', 'C', 'S'];
+
+//Reads a single suit letter, in either case
+pub fn suit_index_of_letter(letter: &str) -> Option<usize> {
+    let mut chars = letter.chars();
+    match (chars.next(), chars.next()) {
+        (Some(letter), None) => SUIT_LETTERS.iter().position(|suit_letter| suit_letter.eq_ignore_ascii_case(&letter)),
+        _ => None,
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub enum Suit {
     HEARTS,
@@ -52,10 +102,10 @@ impl Suit {
     }
 
     pub fn same_color(&self, other: Suit) -> bool {
-        return if *self == Self::HEARTS || *self == Self::DIAMONDS {
+        if *self == Self::HEARTS || *self == Self::DIAMONDS {
             other == Self::HEARTS || other == Self::DIAMONDS
         } else {
             other == Self::CLUBS || other == Self::SPADES
         }
     }
-}
\ No newline at end of file
+}
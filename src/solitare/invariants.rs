@@ -0,0 +1,209 @@
+use std::fmt::{Display, Formatter};
+use crate::solitare::card::Card;
+use crate::solitare::solitare::Game;
+
+//Something no sequence of legal moves can lead to - a sign a move was made wrongly
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InvariantError {
+    DuplicateCard(Card),
+    MissingCard(Card),
+    BrokenRun { stack_index: usize, upturned_index: usize }, //The upturned card doesn't alternate colour or descend by one from the card below it
+    WrongSuit { suit_index: usize, card: Card }, //Each ace stack holds a single suit
+    OutOfSequence { suit_index: usize, card: Card }, //Ace stacks ascend by one from the ace
+    TooManyRedeals(u8),
+}
+
+impl Display for InvariantError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvariantError::DuplicateCard(card) => write!(f, "{} appears more than once", card),
+            InvariantError::MissingCard(card) => write!(f, "{} is missing", card),
+            InvariantError::BrokenRun { stack_index, upturned_index } => write!(f, "table stack {} breaks its run at upturned card {}", stack_index + 1, upturned_index + 1),
+            InvariantError::WrongSuit { suit_index, card } => write!(f, "{} is on ace stack {}", card, suit_index),
+            InvariantError::OutOfSequence { suit_index, card } => write!(f, "{} is out of sequence on ace stack {}", card, suit_index),
+            InvariantError::TooManyRedeals(redeals_used) => write!(f, "{} redeals used, more than the rules allow", redeals_used),
+        }
+    }
+}
+
+impl std::error::Error for InvariantError {}
+
+
+//What a game that holds its invariants still has worth noting
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct InvariantReport {
+    //Stacks with downturned cards but nothing upturned. Legal, as flipping is a move of its own, but nothing
+    //else can happen on the stack until it's flipped
+    pub unflipped: [bool; 7],
+}
+
+impl InvariantReport {
+    pub fn has_unflipped(&self) -> bool {
+        self.unflipped.contains(&true)
+    }
+}
+
+
+impl Game {
+    //Checks the game is one legal play could have reached: all 52 cards exactly once, upturned runs alternating
+    //colour and descending, and ace stacks ascending in their own suit. Cheap enough to run after every move
+    pub fn check_invariants(&self) -> Result<InvariantReport, InvariantError> {
+        let mut seen = [false; 52];
+        let mut place = |cards: &[Card]| -> Result<(), InvariantError> {
+            for &card in cards {
+                if seen[card.index()] {
+                    return Err(InvariantError::DuplicateCard(card));
+                }
+                seen[card.index()] = true;
+            }
+            Ok(())
+        };
+
+        let mut report = InvariantReport::default();
+
+        for (stack_index, table_stack) in self.table.iter().enumerate() {
+            place(&table_stack.downturned)?;
+            place(&table_stack.upturned)?;
+
+            for (upturned_index, pair) in table_stack.upturned.windows(2).enumerate() {
+                let (under, over) = (pair[0], pair[1]);
+                if under.suit().same_color(over.suit()) || under.number() != over.number() + 1 {
+                    return Err(InvariantError::BrokenRun { stack_index, upturned_index: upturned_index + 1 });
+                }
+            }
+
+            report.unflipped[stack_index] = table_stack.upturned.is_empty() && !table_stack.downturned.is_empty();
+        }
+
+        for (suit_index, ace_stack) in self.aces.iter().enumerate() {
+            place(&ace_stack.ace_stack)?;
+
+            for (height, &card) in ace_stack.ace_stack.iter().enumerate() {
+                if card.suit_index() as usize != suit_index {
+                    return Err(InvariantError::WrongSuit { suit_index, card });
+                }
+                if card.number() as usize != height + 1 {
+                    return Err(InvariantError::OutOfSequence { suit_index, card });
+                }
+            }
+        }
+
+        place(&self.draw)?;
+        place(&self.waste)?;
+
+        if let Some(card_index) = (0..52).find(|&card_index| !seen[card_index as usize]) {
+            return Err(InvariantError::MissingCard(Card::new(card_index)));
+        }
+
+        //can_redeal is checked before each redeal, so the last one used must have been allowed
+        if self.redeals_used > 0 && !self.rules.can_redeal(self.redeals_used - 1) {
+            return Err(InvariantError::TooManyRedeals(self.redeals_used));
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solitare::rules::Redeals;
+    use super::*;
+
+    fn card(token: &str) -> Card {
+        token.parse().unwrap()
+    }
+
+    #[test]
+    fn dealt_games_hold() {
+        for seed in 0..20 {
+            let report = Game::from_seed(seed).check_invariants().unwrap();
+            assert!(!report.has_unflipped());
+        }
+    }
+
+    #[test]
+    fn duplicate_card() {
+        let mut game = Game::from_seed(1);
+        let table_card = game.table[0].upturned[0];
+        game.draw[0] = table_card;
+
+        assert_eq!(game.check_invariants(), Err(InvariantError::DuplicateCard(table_card)));
+    }
+
+    #[test]
+    fn missing_card() {
+        let mut game = Game::from_seed(1);
+        let draw_card = game.draw.pop().unwrap();
+
+        assert_eq!(game.check_invariants(), Err(InvariantError::MissingCard(draw_card)));
+    }
+
+    #[test]
+    fn broken_run() {
+        let mut game = Game::from_seed(1);
+        let downturned_card = game.table[4].downturned.pop().unwrap();
+        game.table[4].upturned.push(downturned_card);
+
+        assert_eq!(game.check_invariants(), Err(InvariantError::BrokenRun { stack_index: 4, upturned_index: 1 }));
+    }
+
+    //Hearts up to 3H and diamonds up to AD on the aces
+    fn with_aces() -> Game {
+        let mut game = Game::from_seed(1);
+        for token in ["AH", "2H", "3H", "AD"] {
+            let card = card(token);
+            for table_stack in &mut game.table {
+                if let Some(index) = table_stack.downturned.iter().position(|&other| other == card) {
+                    table_stack.downturned.remove(index);
+                }
+                if let Some(index) = table_stack.upturned.iter().position(|&other| other == card) {
+                    table_stack.upturned.remove(index);
+                }
+            }
+            if let Some(index) = game.draw.iter().position(|&other| other == card) {
+                game.draw.remove(index);
+            }
+            game.aces[card.suit_index() as usize].ace_stack.push(card);
+        }
+        game
+    }
+
+    #[test]
+    fn ace_stack_out_of_order() {
+        let mut game = with_aces();
+        game.aces[0].ace_stack.swap(1, 2);
+
+        assert_eq!(game.check_invariants(), Err(InvariantError::OutOfSequence { suit_index: 0, card: card("3H") }));
+    }
+
+    #[test]
+    fn ace_stack_wrong_suit() {
+        let mut game = with_aces();
+        let ace = game.aces[1].ace_stack.pop().unwrap();
+        game.aces[0].ace_stack.push(ace);
+
+        assert_eq!(game.check_invariants(), Err(InvariantError::WrongSuit { suit_index: 0, card: card("AD") }));
+    }
+
+    #[test]
+    fn too_many_redeals() {
+        let mut game = Game::from_seed(1);
+        game.rules.redeals = Redeals::Limited(1);
+        game.redeals_used = 1;
+        assert!(game.check_invariants().is_ok());
+
+        game.redeals_used = 2;
+        assert_eq!(game.check_invariants(), Err(InvariantError::TooManyRedeals(2)));
+    }
+
+    #[test]
+    fn unflipped_stack_is_reported() {
+        let mut game = Game::from_seed(1);
+        let upturned_card = game.table[2].upturned.pop().unwrap();
+        game.waste.push(upturned_card);
+
+        let report = game.check_invariants().unwrap();
+        assert!(report.has_unflipped());
+        assert_eq!(report.unflipped, [false, false, true, false, false, false, false]);
+    }
+}
@@ -0,0 +1,309 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use crate::solitare::card::{suit_index_of_letter, Card, SUIT_LETTERS};
+use crate::solitare::pile::Pile;
+use crate::solitare::rules::Rules;
+use crate::solitare::solitare::{AceStack, Game, TableStack};
+
+//A readable layout of a Game at any point of play, one item per line:
+//  draw three
+//  redeals unlimited
+//  recycled 0                     times the waste has been turned back over
+//  foundation H:0 D:2 C:0 S:1     height of each ace stack
+//  stock 5S 7C 2H                 bottom card first - the top card is drawn next
+//  waste 3H KS                    bottom card first - the top card is playable
+//  1: KH QS
+//  2: 9c 3d 8H                    one line per table stack, bottom card first. A lowercase suit marks a downturned card
+//  ... through to 7:
+//Cards are a rank (A 2-9 T J Q K) then a suit (H D C S). Blank lines and lines starting with # are ignored
+#[derive(Debug, Eq, PartialEq)]
+pub struct LayoutError {
+    pub line: usize, //Counting from 1, 0 for the layout as a whole
+    pub message: String,
+}
+
+impl Display for LayoutError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+impl Display for Game {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "draw {}", self.rules.draw)?;
+        writeln!(f, "redeals {}", self.rules.redeals)?;
+        writeln!(f, "recycled {}", self.redeals_used)?;
+
+        write!(f, "foundation")?;
+        for (suit_letter, ace_stack) in SUIT_LETTERS.iter().zip(&self.aces) {
+            write!(f, " {}:{}", suit_letter, ace_stack.ace_stack.len())?;
+        }
+        writeln!(f)?;
+
+        write!(f, "stock")?;
+        for card in &self.draw {
+            write!(f, " {}", card)?;
+        }
+        writeln!(f)?;
+
+        write!(f, "waste")?;
+        for card in &self.waste {
+            write!(f, " {}", card)?;
+        }
+        writeln!(f)?;
+
+        for (stack_index, table_stack) in self.table.iter().enumerate() {
+            write!(f, "{}:", stack_index + 1)?;
+            for card in &table_stack.downturned {
+                write!(f, " {}", downturned_token(card))?;
+            }
+            for card in &table_stack.upturned {
+                write!(f, " {}", card)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Game {
+    type Err = LayoutError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = Parts::new();
+        let mut stacks_given = [false; 7];
+
+        for (line_index, line) in s.lines().enumerate() {
+            let error = |message: String| LayoutError { line: line_index + 1, message };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue
+            }
+
+            let (keyword, rest) = line.split_once(|c: char| c.is_whitespace()).unwrap_or((line, ""));
+            let tokens = rest.split_whitespace();
+            match keyword {
+                "draw" => parts.rules.draw = rest.trim().parse().map_err(error)?,
+                "redeals" => parts.rules.redeals = rest.trim().parse().map_err(error)?,
+                "recycled" => parts.redeals_used = rest.trim().parse().map_err(|_| error(format!("bad recycled count '{}'", rest)))?,
+                "foundation" => {
+                    for token in tokens {
+                        let (suit_letter, height) = token.split_once(':').ok_or_else(|| error(format!("bad foundation '{}', expected like H:3", token)))?;
+                        let suit_index = suit_index_of_letter(suit_letter)
+                            .ok_or_else(|| error(format!("bad suit '{}'", suit_letter)))?;
+                        parts.foundation_heights[suit_index] = height.parse().map_err(|_| error(format!("bad foundation height '{}'", height)))?;
+                    }
+                },
+                "stock" => parts.draw = tokens.map(|token| token.parse()).collect::<Result<_, _>>().map_err(error)?,
+                "waste" => parts.waste = tokens.map(|token| token.parse()).collect::<Result<_, _>>().map_err(error)?,
+                _ => {
+                    let stack_index = keyword.strip_suffix(':')
+                        .and_then(|stack_number| stack_number.parse::<usize>().ok())
+                        .filter(|stack_number| (1..=7).contains(stack_number))
+                        .map(|stack_number| stack_number - 1)
+                        .ok_or_else(|| error(format!("unknown line '{}'", line)))?;
+
+                    let (downturned, upturned) = &mut parts.table[stack_index];
+                    for token in tokens {
+                        let card: Card = token.parse().map_err(error)?;
+
+                        if token.ends_with(|c: char| c.is_ascii_lowercase()) {
+                            if !upturned.is_empty() {
+                                return Err(error(format!("downturned {} is on top of upturned cards", token)));
+                            }
+                            downturned.push(card);
+                        } else {
+                            upturned.push(card);
+                        }
+                    }
+                    stacks_given[stack_index] = true;
+                },
+            }
+        }
+
+        if let Some(stack_index) = stacks_given.iter().position(|given| !given) {
+            return Err(LayoutError { line: 0, message: format!("no line for table stack {}", stack_index + 1) });
+        }
+
+        parts.into_game().map_err(|message| LayoutError { line: 0, message })
+    }
+}
+
+
+//A position as read from any of the text formats, before it's checked and put together into a Game
+pub(crate) struct Parts {
+    pub table: [(Vec<Card>, Vec<Card>); 7], //Downturned then upturned cards of each stack, bottom card first
+    pub foundation_heights: [i8; 4], //By suit index
+    pub draw: Vec<Card>, //Bottom card first
+    pub waste: Vec<Card>, //Bottom card first
+    pub rules: Rules,
+    pub redeals_used: u8,
+}
+
+impl Parts {
+    pub fn new() -> Self {
+        Self {
+            table: Default::default(),
+            foundation_heights: [0; 4],
+            draw: Vec::new(),
+            waste: Vec::new(),
+            rules: Rules::default(),
+            redeals_used: 0,
+        }
+    }
+
+    //Checks every card turns up exactly once and each pile fits, then builds the Game
+    pub fn into_game(self) -> Result<Game, String> {
+        let mut seen = [false; 52];
+        let mut place = |cards: &[Card]| -> Result<(), String> {
+            for card in cards {
+                if seen[card.index()] {
+                    return Err(format!("{} appears more than once", card));
+                }
+                seen[card.index()] = true;
+            }
+            Ok(())
+        };
+
+        let mut aces: [AceStack; 4] = core::array::from_fn(|_| AceStack { ace_stack: Pile::new() });
+        for (suit_index, &height) in self.foundation_heights.iter().enumerate() {
+            if !(0..=13).contains(&height) {
+                return Err(format!("bad foundation height {}", height));
+            }
+            for number in 1..=height {
+                aces[suit_index].ace_stack.push(Card::new(suit_index as i8 * 13 + number - 1));
+            }
+            place(&aces[suit_index].ace_stack)?;
+        }
+
+        for (stack_index, (downturned, upturned)) in self.table.iter().enumerate() {
+            if downturned.len() > 6 {
+                return Err(format!("table stack {} has more than 6 downturned cards", stack_index + 1));
+            }
+            if upturned.len() > 13 {
+                return Err(format!("table stack {} has more than 13 upturned cards", stack_index + 1));
+            }
+            place(downturned)?;
+            place(upturned)?;
+        }
+
+        if self.draw.len() + self.waste.len() > 24 {
+            return Err(String::from("more than 24 cards in the stock and waste"));
+        }
+        place(&self.draw)?;
+        place(&self.waste)?;
+
+        if let Some(card_index) = (0..52).find(|&card_index| !seen[card_index as usize]) {
+            return Err(format!("{} is missing", Card::new(card_index)));
+        }
+
+        let game = Game {
+            table: self.table.map(|(downturned, upturned)| TableStack {
+                downturned: Pile::from_slice(&downturned),
+                upturned: Pile::from_slice(&upturned),
+            }),
+            aces,
+            draw: Pile::from_slice(&self.draw),
+            waste: Pile::from_slice(&self.waste),
+            rules: self.rules,
+            redeals_used: self.redeals_used,
+        };
+        game.check_invariants().map_err(|invariant_error| invariant_error.to_string())?;
+
+        Ok(game)
+    }
+}
+
+//Rank as usual, suit in lowercase
+pub(crate) fn downturned_token(card: &Card) -> String {
+    let token = card.to_string();
+    let (rank, suit) = token.split_at(token.len() - 1);
+    format!("{}{}", rank, suit.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use crate::solitare::apply::apply;
+    use crate::solitare::rules::{DrawMode, Redeals};
+    use crate::solitare::state::GameMove;
+    use super::*;
+
+    fn assert_round_trips(game: &Game) {
+        let layout = game.to_string();
+        let parsed: Game = layout.parse().unwrap_or_else(|layout_error| panic!("{} reading back\n{}", layout_error, layout));
+        assert!(parsed == *game, "read back differently:\n{}", layout);
+    }
+
+    #[test]
+    fn seeded_deals_round_trip() {
+        for seed in 0..50 {
+            assert_round_trips(&Game::from_seed(seed));
+        }
+    }
+
+    #[test]
+    fn rules_and_recycles_round_trip() {
+        let mut game = Game::from_seed(1);
+        game.rules.draw = DrawMode::One;
+        game.rules.redeals = Redeals::Limited(2);
+        game.redeals_used = 1;
+        assert_round_trips(&game);
+
+        game.rules.draw = DrawMode::Open;
+        game.rules.redeals = Redeals::Unlimited;
+        assert_round_trips(&game);
+    }
+
+    #[test]
+    fn positions_part_way_through_round_trip() {
+        for seed in 0..30 {
+            let mut game = Game::from_seed(seed);
+            let mut rng = StdRng::seed_from_u64(seed);
+
+            for _ in 0..60 {
+                let game_moves: Vec<GameMove> = game.get_game_state().get_all_moves_youch().into_iter().cloned().collect();
+                if game_moves.is_empty() {
+                    break
+                }
+                apply(&mut game, &game_moves[rng.gen_range(0..game_moves.len())]).expect("Generated moves are always legal");
+                assert_round_trips(&game);
+            }
+        }
+    }
+
+    fn parse_error(layout: &str) -> LayoutError {
+        match layout.parse::<Game>() {
+            Ok(_) => panic!("read\n{}\nwithout an error", layout),
+            Err(layout_error) => layout_error,
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        let layout = Game::from_seed(3).to_string();
+        let with_line = |line_index: usize, line: &str| -> String {
+            let mut lines: Vec<&str> = layout.lines().collect();
+            lines[line_index] = line;
+            lines.join("\n")
+        };
+
+        //Lines are draw, redeals, recycled, foundation, stock, waste, then stacks 1 to 7
+        assert_eq!(parse_error(&with_line(0, "draw four")).line, 1);
+        assert_eq!(parse_error(&with_line(3, "foundation X:0")), LayoutError { line: 4, message: String::from("bad suit 'X'") });
+        assert_eq!(parse_error(&with_line(3, "foundation H")).line, 4);
+        assert_eq!(parse_error(&with_line(6, "1: QQ")).line, 7);
+        assert_eq!(parse_error(&with_line(6, "sideways")), LayoutError { line: 7, message: String::from("unknown line 'sideways'") });
+        assert_eq!(parse_error(&with_line(7, "2: TC Ts")), LayoutError { line: 8, message: String::from("downturned Ts is on top of upturned cards") });
+
+        //Checks of the position as a whole
+        assert_eq!(parse_error(&with_line(6, "1: TC")).line, 0);
+        assert!(parse_error(&with_line(6, "1: TC")).message.contains("appears more than once"));
+        assert_eq!(parse_error(&with_line(6, "1:")), LayoutError { line: 0, message: String::from("QS is missing") });
+        assert_eq!(parse_error(&with_line(12, "")), LayoutError { line: 0, message: String::from("no line for table stack 7") });
+    }
+}
@@ -1,3 +1,15 @@
+pub mod apply;
 pub mod card;
+pub mod invariants;
+pub mod layout;
+pub mod pile;
+pub mod pysol;
+pub mod record;
+pub mod render;
+pub mod rules;
+pub mod shorthand;
+#[allow(clippy::module_inception)] //The game itself, named before the module grew around it
 pub mod solitare;
-pub mod state;
\ No newline at end of file
+pub mod solvitaire;
+pub mod state;
+pub mod view;
\ No newline at end of file
@@ -0,0 +1,105 @@
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::ops::{Deref, DerefMut};
+use crate::solitare::card::Card;
+
+//A fixed capacity stack of cards stored inline, so a whole Game is Copy and cloning it never allocates
+//Dereferences to the slice of cards it holds, bottom card first
+#[derive(Clone, Copy)]
+pub struct Pile<const N: usize> {
+    cards: [Card; N],
+    len: u8,
+}
+
+impl<const N: usize> Pile<N> {
+    pub fn new() -> Self {
+        Self { cards: [Card::new(0); N], len: 0 }
+    }
+
+    pub fn from_slice(cards: &[Card]) -> Self {
+        let mut pile = Self::new();
+        pile.extend_from_slice(cards);
+        pile
+    }
+
+    pub fn push(&mut self, card: Card) {
+        assert!((self.len as usize) < N, "Pile of {} cards is full", N);
+        self.cards[self.len as usize] = card;
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<Card> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Some(self.cards[self.len as usize])
+    }
+
+    pub fn extend_from_slice(&mut self, cards: &[Card]) {
+        for &card in cards {
+            self.push(card);
+        }
+    }
+
+    pub fn insert(&mut self, index: usize, card: Card) {
+        let len = self.len as usize;
+        assert!(index <= len);
+        self.push(card);
+        self.cards[index..=len].rotate_right(1);
+    }
+
+    pub fn remove(&mut self, index: usize) -> Card {
+        let len = self.len as usize;
+        assert!(index < len);
+        let card = self.cards[index];
+        self.cards.copy_within((index + 1)..len, index);
+        self.len -= 1;
+        card
+    }
+
+    //Removes and returns every card from index onwards
+    pub fn split_off(&mut self, index: usize) -> Self {
+        let split = Self::from_slice(&self[index..]);
+        self.len = index as u8;
+        split
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl<const N: usize> Default for Pile<N> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<const N: usize> Deref for Pile<N> {
+    type Target = [Card];
+    fn deref(&self) -> &[Card] { &self.cards[..self.len as usize] }
+}
+
+impl<const N: usize> DerefMut for Pile<N> {
+    fn deref_mut(&mut self) -> &mut [Card] { &mut self.cards[..self.len as usize] }
+}
+
+impl<'p, const N: usize> IntoIterator for &'p Pile<N> {
+    type Item = &'p Card;
+    type IntoIter = std::slice::Iter<'p, Card>;
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
+
+//Only the cards held count - not whatever is left in the unused slots
+impl<const N: usize> PartialEq for Pile<N> {
+    fn eq(&self, other: &Self) -> bool { **self == **other }
+}
+impl<const N: usize> Eq for Pile<N> {}
+impl<const N: usize> Hash for Pile<N> {
+    fn hash<H: Hasher>(&self, state: &mut H) { (**self).hash(state) }
+}
+
+impl<const N: usize> Debug for Pile<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
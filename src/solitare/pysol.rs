@@ -0,0 +1,98 @@
+use crate::solitare::card::{suit_index_of_letter, Card, SUIT_LETTERS};
+use crate::solitare::layout::Parts;
+use crate::solitare::solitare::Game;
+
+//The board text PySolFC's tools write for a Klondike deal:
+//  Talon: 7C 9H ...
+//  Foundations: H-0 C-0 D-0 S-0
+//  : <9S> <3D> KH        one line per tableau column, bottom card first, downturned cards in angle brackets
+//The talon is listed from its top card, the next to be drawn. Foundations give the top rank of each suit, 0 when empty.
+//The ':' starting a column line is optional when reading, but written so empty columns still get a line. A Waste line, listed from its bottom card, is written for positions with a waste,
+//which PySolFC's own deals never have.
+//Only boards are read and written - PySolFC's game numbers come from its own random number generator and dealing
+//code, which aren't reproduced here, so a --seed is never a PySolFC game number. Have PySolFC (or
+//make_pysol_freecell_board.py from the pysol_cards package) write the deal out, and import that
+pub fn to_pysol(game: &Game) -> String {
+    let mut board = String::from("Talon:");
+    for card in game.draw.iter().rev() {
+        board += &format!(" {}", card);
+    }
+
+    board += "\nFoundations:";
+    for suit_index in [0, 2, 1, 3] {
+        let rank = match game.aces[suit_index].ace_stack.last() {
+            Some(card) => card.to_string()[..1].to_string(), //Every rank is a single character
+            None => String::from("0"),
+        };
+        board += &format!(" {}-{}", SUIT_LETTERS[suit_index], rank);
+    }
+    board += "\n";
+
+    if !game.waste.is_empty() {
+        board += "Waste:";
+        for card in &game.waste {
+            board += &format!(" {}", card);
+        }
+        board += "\n";
+    }
+
+    for table_stack in &game.table {
+        let tokens: Vec<String> = table_stack.downturned.iter().map(|card| format!("<{}>", card))
+            .chain(table_stack.upturned.iter().map(Card::to_string))
+            .collect();
+        board += ":";
+        for token in tokens {
+            board += &format!(" {}", token);
+        }
+        board += "\n";
+    }
+
+    board
+}
+
+pub fn from_pysol(s: &str) -> Result<Game, String> {
+    let mut parts = Parts::new();
+    let mut columns = 0;
+
+    for line in s.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue
+        }
+
+        if let Some(talon) = line.strip_prefix("Talon:") {
+            parts.draw = talon.split_whitespace().rev().map(|token| token.parse()).collect::<Result<_, _>>()?;
+        } else if let Some(waste) = line.strip_prefix("Waste:") {
+            parts.waste = waste.split_whitespace().map(|token| token.parse()).collect::<Result<_, _>>()?;
+        } else if let Some(foundations) = line.strip_prefix("Foundations:") {
+            for foundation in foundations.split_whitespace() {
+                let (suit_letter, rank) = foundation.split_once('-').ok_or_else(|| format!("bad foundation '{}', expected like H-0", foundation))?;
+                let suit_index = suit_index_of_letter(suit_letter)
+                    .ok_or_else(|| format!("bad suit '{}'", suit_letter))?;
+                parts.foundation_heights[suit_index] = match rank {
+                    "0" => 0,
+                    rank => format!("{}{}", rank, suit_letter).parse::<Card>()?.number(),
+                };
+            }
+        } else {
+            if columns == 7 {
+                return Err(format!("more than 7 columns at '{}'", line));
+            }
+            let (downturned, upturned) = &mut parts.table[columns];
+            for token in line.trim_start_matches(':').split_whitespace() {
+                match token.strip_prefix('<').and_then(|token| token.strip_suffix('>')) {
+                    Some(token) if upturned.is_empty() => downturned.push(token.parse()?),
+                    Some(token) => return Err(format!("downturned {} is on top of upturned cards", token)),
+                    None => upturned.push(token.parse()?),
+                }
+            }
+            columns += 1;
+        }
+    }
+
+    if columns != 7 {
+        return Err(format!("Klondike has 7 columns, not {}", columns));
+    }
+
+    parts.into_game()
+}
@@ -0,0 +1,153 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use crate::solitare::apply::apply;
+use crate::solitare::rules::Rules;
+use crate::solitare::shorthand::resolve_move;
+use crate::solitare::solitare::{DeckError, Game};
+use crate::solitare::state::GameMove;
+
+//A game written down - how it was dealt, the rules it was played under and the moves played - so it can be replayed exactly
+//Written one item per line:
+//  seed 42              or   deck 19 24 0 ...  (52 card indices)
+//  draw three
+//  redeals unlimited
+//  t3.2-t5.4            one move per line, in GameMove notation - or a short form play takes, like t3-t5, w-f or flip 6,
+//                       resolved against the position the moves before it reach. Written back in full notation
+//Blank lines and lines starting with # are ignored
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameRecord {
+    pub deal: Deal,
+    pub rules: Rules,
+    pub moves: Vec<GameMove>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Deal {
+    Seed(u64), //Dealt by Game::from_seed
+    Deck([i8; 52]), //Dealt by Game::from_deck
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct RecordError {
+    pub line: usize, //Counting from 1
+    pub message: String,
+}
+
+impl GameRecord {
+    pub fn new(deal: Deal, rules: Rules) -> Self {
+        Self { deal, rules, moves: Vec::new() }
+    }
+
+    //The starting position, before any of the moves
+    pub fn start(&self) -> Result<Game, DeckError> {
+        let game = match &self.deal {
+            Deal::Seed(seed) => Game::from_seed(*seed),
+            Deal::Deck(deck_integers) => Game::from_deck(deck_integers)?,
+        };
+
+        Ok(game.with_rules(self.rules))
+    }
+}
+
+impl Display for GameRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.deal {
+            Deal::Seed(seed) => writeln!(f, "seed {}", seed)?,
+            Deal::Deck(deck_integers) => {
+                write!(f, "deck")?;
+                for card_index in deck_integers {
+                    write!(f, " {}", card_index)?;
+                }
+                writeln!(f)?;
+            },
+        }
+        writeln!(f, "draw {}", self.rules.draw)?;
+        writeln!(f, "redeals {}", self.rules.redeals)?;
+
+        for game_move in &self.moves {
+            writeln!(f, "{}", game_move)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for GameRecord {
+    type Err = RecordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut deal = None;
+        let mut rules = Rules::default();
+        let mut moves = Vec::new();
+        //The position the moves so far reach, for resolving short forms - None once one of them is illegal
+        let mut position: Option<Game> = None;
+
+        for (line_index, line) in s.lines().enumerate() {
+            let error = |message: String| RecordError { line: line_index + 1, message };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue
+            }
+
+            let (keyword, rest) = line.split_once(' ').unwrap_or((line, ""));
+            match keyword {
+                "seed" => deal = Some(Deal::Seed(rest.trim().parse().map_err(|_| error(format!("bad seed '{}'", rest)))?)),
+                "deck" => deal = Some(Deal::Deck(parse_deck(rest).map_err(error)?)),
+                "draw" | "redeals" if !rest.is_empty() && !moves.is_empty() => return Err(error(String::from("rules must come before the moves"))),
+                "draw" if !rest.is_empty() => rules.draw = rest.trim().parse().map_err(error)?,
+                "redeals" => rules.redeals = rest.trim().parse().map_err(error)?,
+                _ => {
+                    let Some(deal) = &deal else {
+                        return Err(error(String::from("moves must come after the seed or deck")));
+                    };
+                    if moves.is_empty() {
+                        position = GameRecord { deal: deal.clone(), rules, moves: Vec::new() }.start().ok();
+                    }
+
+                    let game_move: GameMove = match line.parse() {
+                        Ok(game_move) => game_move,
+                        Err(_) => match &position {
+                            Some(game) => resolve_move(game, line).map_err(error)?,
+                            None => return Err(error(format!("can't resolve '{}' after an illegal move", line))),
+                        },
+                    };
+                    if let Some(game) = &mut position {
+                        if apply(game, &game_move).is_err() {
+                            position = None;
+                        }
+                    }
+                    moves.push(game_move);
+                },
+            }
+        }
+
+        match deal {
+            Some(deal) => Ok(Self { deal, rules, moves }),
+            None => Err(RecordError { line: 0, message: String::from("no seed or deck") }),
+        }
+    }
+}
+
+impl Display for RecordError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for RecordError {}
+
+//Reads 52 card indices separated by spaces or commas - brackets are allowed, so a deck logged by Game::from_seed can be pasted in
+pub fn parse_deck(s: &str) -> Result<[i8; 52], String> {
+    let card_indices = s.split(|c: char| c.is_whitespace() || c == ',' || c == '[' || c == ']')
+        .filter(|token| !token.is_empty())
+        .map(|token| token.parse::<i8>().map_err(|_| format!("bad card index '{}'", token)))
+        .collect::<Result<Vec<i8>, String>>()?;
+
+    let deck_integers: [i8; 52] = card_indices.as_slice().try_into()
+        .map_err(|_| format!("a deck has 52 cards, not {}", card_indices.len()))?;
+
+    //Validate by dealing it
+    Game::from_deck(&deck_integers).map_err(|deck_error| deck_error.to_string())?;
+
+    Ok(deck_integers)
+}
@@ -0,0 +1,141 @@
+use std::fmt::{Display, Formatter};
+use crate::solitare::card::{Card, Suit};
+use crate::solitare::rules::DrawMode;
+use crate::solitare::solitare::Game;
+
+const RED: &str = "\x1b[31m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+//How cards are drawn - plain text by default, so boards can go to files and logs
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct RenderStyle {
+    pub unicode: bool, //Suit symbols and shaded face-down cards
+    pub color: bool, //ANSI colours - red suits in red, face-down cards dimmed
+}
+
+impl RenderStyle {
+    pub const PLAIN: Self = Self { unicode: false, color: false };
+    pub const UNICODE: Self = Self { unicode: true, color: false };
+    pub const COLOR: Self = Self { unicode: true, color: true };
+}
+
+//The game drawn as a board, for a terminal:
+//  stock 14  waste 7  9C 4H 2D  foundation AH -- 3C --
+//    1   2   3   4   5   6   7
+//   KH  ##  ##  ##  ##  ##  ##
+//   QS  8D  ##  ##  ##  ##  ##
+//The tableau reads down the screen, bottom card first, with ## for downturned cards. The waste shows the
+//cards that were last drawn, the rightmost playable, and the foundation the top card of each ace stack
+pub struct Board<'a> {
+    game: &'a Game,
+    style: RenderStyle,
+}
+
+impl Game {
+    pub fn board(&self, style: RenderStyle) -> Board<'_> {
+        Board { game: self, style }
+    }
+}
+
+impl Board<'_> {
+    //Every card is two characters wide, however it's styled, so the columns line up
+    fn write_card(&self, f: &mut Formatter<'_>, card: Card) -> std::fmt::Result {
+        let red = matches!(card.suit(), Suit::HEARTS | Suit::DIAMONDS);
+        let text = if self.style.unicode {
+            let symbol = match card.suit() {
+                Suit::HEARTS => '♥',
+                Suit::DIAMONDS => '♦',
+                Suit::CLUBS => '♣',
+                Suit::SPADES => '♠',
+            };
+            format!("{}{}", card.to_string().chars().next().unwrap(), symbol)
+        } else {
+            card.to_string()
+        };
+
+        if self.style.color && red {
+            write!(f, "{}{}{}", RED, text, RESET)
+        } else {
+            write!(f, "{}", text)
+        }
+    }
+
+    fn write_downturned(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let text = if self.style.unicode { "░░" } else { "##" };
+        if self.style.color {
+            write!(f, "{}{}{}", DIM, text, RESET)
+        } else {
+            write!(f, "{}", text)
+        }
+    }
+}
+
+impl Display for Board<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let game = self.game;
+
+        //Stock and waste - in open draw every stock card is playable, so there's no waste to speak of
+        write!(f, "stock {:<4}", game.draw.len())?;
+        if game.rules.draw != DrawMode::Open {
+            write!(f, "waste {:<2}", game.waste.len())?;
+            let shown = game.rules.draw_count().min(game.waste.len());
+            for &card in &game.waste[game.waste.len() - shown..] {
+                write!(f, " ")?;
+                self.write_card(f, card)?;
+            }
+            //Pad short wastes so the foundation always starts in the same place
+            for _ in shown..game.rules.draw_count() {
+                write!(f, "   ")?;
+            }
+        }
+
+        write!(f, "  foundation")?;
+        for ace_stack in &game.aces {
+            write!(f, " ")?;
+            match ace_stack.ace_stack.last() {
+                Some(&card) => self.write_card(f, card)?,
+                None => write!(f, "--")?,
+            }
+        }
+        writeln!(f)?;
+
+        //Tableau, one row per depth
+        for stack_index in 0..game.table.len() {
+            write!(f, "{:>3}", stack_index + 1)?;
+            if stack_index + 1 < game.table.len() {
+                write!(f, " ")?;
+            }
+        }
+        writeln!(f)?;
+
+        let depth = game.table.iter()
+            .map(|table_stack| table_stack.downturned.len() + table_stack.upturned.len())
+            .max()
+            .unwrap_or(0);
+        for row in 0..depth {
+            //Trailing blanks are left off, but the columns before the last card still need their gaps
+            let last = game.table.iter()
+                .rposition(|table_stack| row < table_stack.downturned.len() + table_stack.upturned.len())
+                .unwrap_or(0);
+
+            for (stack_index, table_stack) in game.table.iter().enumerate().take(last + 1) {
+                write!(f, " ")?;
+                let downturned = table_stack.downturned.len();
+                if row < downturned {
+                    self.write_downturned(f)?;
+                } else if let Some(&card) = table_stack.upturned.get(row - downturned) {
+                    self.write_card(f, card)?;
+                } else {
+                    write!(f, "  ")?;
+                }
+                if stack_index < last {
+                    write!(f, " ")?;
+                }
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum DrawMode {
+    Open, //Thoughtful solitaire - every stock card can be played at any time
+    One, //Turn one card from the stock onto the waste at a time
+    Three, //Turn three cards at a time, only the top of the waste is playable
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Redeals {
+    Unlimited,
+    Limited(u8), //Number of times the waste may be turned back over into the stock
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Rules {
+    pub draw: DrawMode,
+    pub redeals: Redeals,
+}
+
+impl Rules {
+    pub fn draw_count(&self) -> usize {
+        match self.draw {
+            DrawMode::Open => 0,
+            DrawMode::One => 1,
+            DrawMode::Three => 3,
+        }
+    }
+
+    pub fn can_redeal(&self, redeals_used: u8) -> bool {
+        match self.redeals {
+            Redeals::Unlimited => true,
+            Redeals::Limited(redeals) => redeals_used < redeals,
+        }
+    }
+}
+
+impl Default for Rules {
+    //Klondike as it is usually played
+    fn default() -> Self {
+        Self {
+            draw: DrawMode::Three,
+            redeals: Redeals::Unlimited,
+        }
+    }
+}
+
+impl Display for DrawMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DrawMode::Open => write!(f, "open"),
+            DrawMode::One => write!(f, "one"),
+            DrawMode::Three => write!(f, "three"),
+        }
+    }
+}
+
+impl FromStr for DrawMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "open" => Ok(DrawMode::Open),
+            "one" | "1" => Ok(DrawMode::One),
+            "three" | "3" => Ok(DrawMode::Three),
+            _ => Err(format!("unknown draw mode '{}', expected open, one or three", s)),
+        }
+    }
+}
+
+impl Display for Redeals {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Redeals::Unlimited => write!(f, "unlimited"),
+            Redeals::Limited(redeals) => write!(f, "{}", redeals),
+        }
+    }
+}
+
+impl FromStr for Redeals {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "unlimited" => Ok(Redeals::Unlimited),
+            _ => s.parse().map(Redeals::Limited).map_err(|_| format!("unknown redeals '{}', expected unlimited or a number", s)),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use crate::solitare::apply::apply;
+use crate::solitare::solitare::Game;
+use crate::solitare::state::{CardPosition, GameMove};
+
+//Reads a move the way a player types it, leaving out whatever the game makes obvious:
+//  t3-t5         the run from stack 3 that fits onto stack 5
+//  t3-f          the last card of stack 3 up to its ace stack
+//  w-t2, s12-f   a stock card onto a stack or up to its ace stack
+//  fH-t4         the top card of an ace stack back down onto a stack
+//  flip 6        turn over the last downturned card of stack 6 - or just flip, when only one stack needs it
+//Full move notation works as well, and is taken as it is. A short form has to fit exactly one legal move
+pub fn resolve_move(game: &Game, text: &str) -> Result<GameMove, String> {
+    let text = text.trim();
+    if let Ok(game_move) = text.parse::<GameMove>() {
+        return Ok(game_move);
+    }
+
+    let candidates: Vec<GameMove> = match text.strip_prefix("flip") {
+        Some(stack) => {
+            let stack = stack.trim();
+            let stack_indices = if stack.is_empty() { (0..7).collect() } else { vec![stack_number(stack.trim_start_matches(['t', 'd']), text)?] };
+
+            stack_indices.into_iter()
+                .filter_map(|stack_index| {
+                    let downturned_len = game.table[stack_index as usize].downturned.len();
+                    (downturned_len != 0).then(|| GameMove {
+                        from: CardPosition::TableDownturned { stack_index, downturned_index: downturned_len as i8 - 1 },
+                        to: CardPosition::TableUpturned { stack_index, upturned_index: 0 },
+                    })
+                })
+                .collect()
+        },
+        None => {
+            let (from, to) = text.split_once('-').ok_or_else(|| format!("bad move '{}'", text))?;
+            let froms = sources(game, from.trim())?;
+            let tos = destinations(game, to.trim())?;
+
+            froms.iter()
+                .flat_map(|from| tos.iter().map(move |to| GameMove { from: from.clone(), to: to.clone() }))
+                .collect()
+        },
+    };
+
+    let mut legal: Vec<GameMove> = candidates.into_iter()
+        .filter(|game_move| {
+            let mut trial = *game;
+            apply(&mut trial, game_move).is_ok()
+        })
+        .collect();
+
+    match legal.len() {
+        0 => Err(format!("no legal move fits '{}'", text)),
+        1 => Ok(legal.remove(0)),
+        _ => {
+            let fits: Vec<String> = legal.iter().map(GameMove::to_string).collect();
+            Err(format!("'{}' could be any of {}", text, fits.join(", ")))
+        },
+    }
+}
+
+//Stack numbers count from 1, as in the notation
+fn stack_number(digits: &str, text: &str) -> Result<i8, String> {
+    match digits.parse::<i8>() {
+        Ok(number @ 1..=7) => Ok(number - 1),
+        _ => Err(format!("bad stack '{}' in '{}'", digits, text)),
+    }
+}
+
+//Every position the cards could be taken from
+fn sources(game: &Game, position: &str) -> Result<Vec<CardPosition>, String> {
+    if let Ok(position) = position.parse() {
+        return Ok(vec![position]);
+    }
+    if !position.is_ascii() {
+        return Err(format!("bad position '{}'", position));
+    }
+
+    match position.split_at(position.len().min(1)) {
+        ("t", digits) => {
+            let stack_index = stack_number(digits, position)?;
+            let upturned_len = game.table[stack_index as usize].upturned.len() as i8;
+            Ok((0..upturned_len).map(|upturned_index| CardPosition::TableUpturned { stack_index, upturned_index }).collect())
+        },
+        ("f", "") => Ok(ace_stacks()),
+        _ => Err(format!("bad position '{}'", position)),
+    }
+}
+
+//Every position the cards could go to
+fn destinations(game: &Game, position: &str) -> Result<Vec<CardPosition>, String> {
+    if let Ok(position) = position.parse() {
+        return Ok(vec![position]);
+    }
+    if !position.is_ascii() {
+        return Err(format!("bad position '{}'", position));
+    }
+
+    match position.split_at(position.len().min(1)) {
+        ("t", digits) => {
+            //Cards only ever go on the end of a stack
+            let stack_index = stack_number(digits, position)?;
+            let upturned_index = game.table[stack_index as usize].upturned.len() as i8;
+            Ok(vec![CardPosition::TableUpturned { stack_index, upturned_index }])
+        },
+        ("f", "") => Ok(ace_stacks()),
+        _ => Err(format!("bad position '{}'", position)),
+    }
+}
+
+fn ace_stacks() -> Vec<CardPosition> {
+    (0..4).map(|suit_index| CardPosition::Ace { suit_index }).collect()
+}
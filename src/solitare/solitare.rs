@@ -1,28 +1,60 @@
 use std::collections::HashMap;
-use std::marker::PhantomData;
+use std::fmt::{Display, Formatter};
 use std::slice;
+use log::info;
 use rand::prelude::SliceRandom;
-use rand::thread_rng;
+use rand::{thread_rng, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use crate::solitare::card::{Card, Suit};
+use crate::solitare::pile::Pile;
+use crate::solitare::rules::{DrawMode, Rules};
 use crate::solitare::state::{CardPosition, GameMove, GameState};
 
-pub struct Game<'a> {
-    pub table: [TableStack<'a>; 7],
-    pub aces: [AceStack<'a>; 4],
-    pub draw: Vec<Card<'a>>,
-    pub _phantom: PhantomData<&'a ()>
+//Fixed size throughout, so positions are cheap to copy, hash and compare
+#[derive(Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Game {
+    pub table: [TableStack; 7],
+    pub aces: [AceStack; 4],
+    pub draw: Pile<24>, //The stock - face down, top card last
+    pub waste: Pile<24>, //Cards turned from the stock, top card last. Unused in open draw mode
+    pub rules: Rules,
+    pub redeals_used: u8,
 }
 
-impl<'a> Game<'a> {
+//A random deal, as from Game::new
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Game {
     pub fn new() -> Self {
-        let mut deck_integers: Vec<i8> = (0..52).collect();
-        deck_integers.shuffle(&mut thread_rng());
+        Self::from_seed(thread_rng().gen())
+    }
 
-        // let deck_integers = vec!(19, 24, 29, 51, 16, 4, 13, 11, 31, 43, 33, 22, 44, 34, 39, 27, 5, 48, 7, 14, 10, 40, 18, 15, 2, 20, 6, 30, 50, 35, 0, 47, 46, 38, 3, 37, 17, 9, 26, 32, 21, 42, 25, 23, 1, 12, 49, 36, 45, 41, 28, 8);
+    //ChaCha8 rather than StdRng, whose algorithm may change between rand versions, so a seed always deals the same game
+    pub fn from_seed(seed: u64) -> Self {
+        let mut deck_integers: [i8; 52] = core::array::from_fn(|i| i as i8);
+        deck_integers.shuffle(&mut ChaCha8Rng::seed_from_u64(seed));
 
-        println!("Created game: {:?}", deck_integers);
+        info!("Created game from seed {}: {:?}", seed, deck_integers);
 
+        Self::from_deck(&deck_integers).expect("Shuffled deck is always valid")
+    }
 
+    pub fn from_deck(deck_integers: &[i8; 52]) -> Result<Self, DeckError> {
+        //Every card index must appear exactly once
+        let mut seen = [false; 52];
+        for &card_index in deck_integers {
+            if !(0..52).contains(&card_index) {
+                return Err(DeckError::CardOutOfRange(card_index));
+            }
+            if seen[card_index as usize] {
+                return Err(DeckError::DuplicateCard(card_index));
+            }
+            seen[card_index as usize] = true;
+        }
 
         let mut deck_integers_iter = deck_integers.iter();
 
@@ -32,7 +64,7 @@ impl<'a> Game<'a> {
         };
 
         let next_n_cards = |deck_integers_iter: &mut slice::Iter<i8>, n: i8| {
-            let mut cards: Vec<Card> = Vec::with_capacity(n as usize);
+            let mut cards: Pile<24> = Pile::new();
 
             for _ in 0..n {
                 let card_index = *deck_integers_iter.next().expect("Drew too many cards");
@@ -42,46 +74,115 @@ impl<'a> Game<'a> {
             cards
         };
 
-        Self {
+        Ok(Self {
             table: [
                 TableStack {
-                    downturned: Vec::new(),
-                    upturned: vec!(next_card(&mut deck_integers_iter))
+                    downturned: Pile::new(),
+                    upturned: Pile::from_slice(&[next_card(&mut deck_integers_iter)])
                 },
                 TableStack {
-                    downturned: vec![next_card(&mut deck_integers_iter)],
-                    upturned: vec!(next_card(&mut deck_integers_iter))
+                    downturned: Pile::from_slice(&[next_card(&mut deck_integers_iter)]),
+                    upturned: Pile::from_slice(&[next_card(&mut deck_integers_iter)])
                 },
                 TableStack {
-                    downturned: vec![next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter)],
-                    upturned: vec!(next_card(&mut deck_integers_iter))
+                    downturned: Pile::from_slice(&[next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter)]),
+                    upturned: Pile::from_slice(&[next_card(&mut deck_integers_iter)])
                 },
                 TableStack {
-                    downturned: vec![next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter)],
-                    upturned: vec!(next_card(&mut deck_integers_iter))
+                    downturned: Pile::from_slice(&[next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter)]),
+                    upturned: Pile::from_slice(&[next_card(&mut deck_integers_iter)])
                 },
                 TableStack {
-                    downturned: vec![next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter)],
-                    upturned: vec!(next_card(&mut deck_integers_iter))
+                    downturned: Pile::from_slice(&[next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter)]),
+                    upturned: Pile::from_slice(&[next_card(&mut deck_integers_iter)])
                 },
                 TableStack {
-                    downturned: vec![next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter)],
-                    upturned: vec!(next_card(&mut deck_integers_iter))
+                    downturned: Pile::from_slice(&[next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter)]),
+                    upturned: Pile::from_slice(&[next_card(&mut deck_integers_iter)])
                 },
                 TableStack {
-                    downturned: vec![next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter)],
-                    upturned: vec!(next_card(&mut deck_integers_iter))
+                    downturned: Pile::from_slice(&[next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter), next_card(&mut deck_integers_iter)]),
+                    upturned: Pile::from_slice(&[next_card(&mut deck_integers_iter)])
                 },
             ],
             aces: [
-                AceStack {ace_stack: Vec::new()},
-                AceStack {ace_stack: Vec::new()},
-                AceStack {ace_stack: Vec::new()},
-                AceStack {ace_stack: Vec::new()}
+                AceStack {ace_stack: Pile::new()},
+                AceStack {ace_stack: Pile::new()},
+                AceStack {ace_stack: Pile::new()},
+                AceStack {ace_stack: Pile::new()}
             ],
             draw: next_n_cards(&mut deck_integers_iter, 24),
-            _phantom: PhantomData::default()
+            waste: Pile::new(),
+            rules: Rules::default(),
+            redeals_used: 0,
+        })
+    }
+
+    //The deck from_deck would deal into this position - only a position straight from the deal has one
+    pub fn deal_order(&self) -> Option<[i8; 52]> {
+        let is_dealt = self.aces.iter().all(|ace_stack| ace_stack.ace_stack.is_empty())
+            && self.waste.is_empty()
+            && self.draw.len() == 24
+            && self.table.iter().enumerate().all(|(stack_index, table_stack)| table_stack.downturned.len() == stack_index && table_stack.upturned.len() == 1);
+        if !is_dealt {
+            return None;
+        }
+
+        //Each stack is dealt downturned cards first then its upturned card, before the rest go to the draw pile
+        let deck_integers: Vec<i8> = self.table.iter()
+            .flat_map(|table_stack| table_stack.downturned.iter().chain(table_stack.upturned.iter()))
+            .chain(self.draw.iter())
+            .map(|card| card.index() as i8)
+            .collect();
+
+        deck_integers.try_into().ok()
+    }
+
+    pub fn with_rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    //The cards of the stock and waste that can currently be played, and where from
+    pub fn get_playable_draw_cards(&self) -> Vec<(&Card, CardPosition)> {
+        match self.rules.draw {
+            DrawMode::Open => {
+                self.draw.iter().enumerate()
+                    .map(|(deck_index, card)| (card, CardPosition::DrawDeck { deck_index: deck_index as i8 }))
+                    .collect()
+            },
+            DrawMode::One | DrawMode::Three => {
+                self.waste.last()
+                    .map(|card| (card, CardPosition::Waste))
+                    .into_iter()
+                    .collect()
+            }
+        }
+    }
+
+    pub fn get_draw_card(&self, position: &CardPosition) -> Option<&Card> {
+        match *position {
+            CardPosition::DrawDeck { deck_index } => self.draw.get(deck_index as usize),
+            CardPosition::Waste => self.waste.last(),
+            _ => None,
+        }
+    }
+
+    pub fn get_stock_moves(&self) -> Vec<GameMove> {
+        //Turn cards from the stock, or the waste back over once the stock runs out
+        let mut moves = Vec::new();
+
+        if self.rules.draw == DrawMode::Open {
+            return moves;
+        }
+
+        if !self.draw.is_empty() {
+            moves.push(GameMove { from: CardPosition::Stock, to: CardPosition::Waste });
+        } else if !self.waste.is_empty() && self.rules.can_redeal(self.redeals_used) {
+            moves.push(GameMove { from: CardPosition::Waste, to: CardPosition::Stock });
         }
+
+        moves
     }
 
     pub fn get_table_flip_moves(&self) -> Vec<GameMove> {
@@ -90,7 +191,7 @@ impl<'a> Game<'a> {
 
         for (stack_index, table_stack) in self.table.iter().enumerate() {
             let downturned_len = table_stack.downturned.len();
-            if table_stack.upturned.len() == 0 && downturned_len != 0 {
+            if table_stack.upturned.is_empty() && downturned_len != 0 {
 
                 moves.push(GameMove {
                     from: CardPosition::TableDownturned {stack_index: stack_index as i8, downturned_index: (downturned_len-1) as i8},
@@ -107,19 +208,16 @@ impl<'a> Game<'a> {
         let mut moves = Vec::new();
 
         for (stack_index, table_stack) in self.table.iter().enumerate() {
-            match table_stack.upturned.last() {
-                Some(final_card) => {
-                    //For each final card, is it an ace?
-                    if final_card.number == 1 {
+            if let Some(final_card) = table_stack.upturned.last() {
+                //For each final card, is it an ace?
+                if final_card.number() == 1 {
 
-                        moves.push(GameMove {
-                            from: CardPosition::TableUpturned { stack_index: stack_index as i8, upturned_index: (table_stack.upturned.len()-1) as i8 },
-                            to: CardPosition::Ace { suit_index: final_card.suit_index }
-                        });
+                    moves.push(GameMove {
+                        from: CardPosition::TableUpturned { stack_index: stack_index as i8, upturned_index: (table_stack.upturned.len()-1) as i8 },
+                        to: CardPosition::Ace { suit_index: final_card.suit_index() }
+                    });
 
-                    }
-                },
-                None => (),
+                }
             }
         }
 
@@ -129,12 +227,12 @@ impl<'a> Game<'a> {
         //Move aces up from deck
         let mut moves = Vec::new();
 
-        for (deck_index, deck_card) in self.draw.iter().enumerate() {
+        for (deck_card, deck_position) in self.get_playable_draw_cards() {
 
-            if deck_card.number == 1 {
+            if deck_card.number() == 1 {
                 moves.push(GameMove {
-                    from: CardPosition::DrawDeck { deck_index: deck_index as i8 },
-                    to: CardPosition::Ace { suit_index: deck_card.suit_index }
+                    from: deck_position,
+                    to: CardPosition::Ace { suit_index: deck_card.suit_index() }
                 })
             }
 
@@ -151,52 +249,51 @@ impl<'a> Game<'a> {
         //Aggregate table queuing kings
         for (stack_index, table_stack) in self.table.iter().enumerate() {
             //Exclude moves from stacks with no downturned cards
-            if table_stack.downturned.len() != 0 {
-                match table_stack.upturned.first() {
-                    Some(first_card) => {
+            if !table_stack.downturned.is_empty() {
+                if let Some(first_card) = table_stack.upturned.first() {
 
-                        //For each root upturned card, is it a king?
-                        if first_card.number == 13 {
+                    //For each root upturned card, is it a king?
+                    if first_card.number() == 13 {
 
-                            table_queuing_kings.push(CardPosition::TableUpturned {
-                                stack_index: stack_index as i8,
-                                upturned_index: 0
-                            })
+                        table_queuing_kings.push(CardPosition::TableUpturned {
+                            stack_index: stack_index as i8,
+                            upturned_index: 0
+                        })
 
 
-                        }
-                    },
-                    None => ()
+                    }
                 }
             }
         }
 
-        //Aggregate draw queuing kings
-        for (draw_index, draw_card) in self.draw.iter().enumerate() {
-            if draw_card.number == 13 {
+        //Aggregate draw queuing kings - only playable ones can move now, but any in the stock or waste will want a space
+        for (draw_card, draw_position) in self.get_playable_draw_cards() {
+            if draw_card.number() == 13 {
 
-                draw_queuing_kings.push(CardPosition::DrawDeck {
-                    deck_index: draw_index as i8
-                });
+                draw_queuing_kings.push(draw_position);
 
             }
         }
+        let waiting_draw_kings = match self.rules.draw {
+            DrawMode::Open => draw_queuing_kings.len(),
+            DrawMode::One | DrawMode::Three => self.draw.iter().chain(&self.waste).filter(|card| card.number() == 13).count(),
+        };
 
-        let vacant_kings: i8 = (table_queuing_kings.len() + draw_queuing_kings.len()) as i8;
+        let vacant_kings: i8 = (table_queuing_kings.len() + waiting_draw_kings) as i8;
 
         //Find a vacant table-stack - Each cycle only 1 move is ever executed at most - therefore we can consider that all of the potential kings move to the same vacant spot
         let mut vacant_stack_index: i8 = 0;
 
         for table_stack in &self.table {
-            if table_stack.upturned.len() == 0
-                && table_stack.downturned.len() == 0
+            if table_stack.upturned.is_empty()
+                && table_stack.downturned.is_empty()
             {
                 break
             }
             vacant_stack_index += 1;
         }
 
-        //Add table moves
+        //Add table moves, then draw moves - table kings first as they also uncover a card
         if vacant_stack_index < 7 {
             for card_position in table_queuing_kings {
                 moves.push(GameMove {
@@ -204,6 +301,12 @@ impl<'a> Game<'a> {
                     to: CardPosition::TableUpturned { stack_index: vacant_stack_index, upturned_index: 0 }
                 })
             }
+            for card_position in draw_queuing_kings {
+                moves.push(GameMove {
+                    from: card_position,
+                    to: CardPosition::TableUpturned { stack_index: vacant_stack_index, upturned_index: 0 }
+                })
+            }
         }
 
         (moves, vacant_kings)
@@ -216,11 +319,8 @@ impl<'a> Game<'a> {
         let mut aces: HashMap<Suit, i8> = HashMap::new();
         for ace_stack in &self.aces {
 
-            match ace_stack.ace_stack.last() {
-                Some(final_ace) => {
-                    aces.insert(final_ace.suit, final_ace.number);
-                },
-                None => (),
+            if let Some(final_ace) = ace_stack.ace_stack.last() {
+                aces.insert(final_ace.suit(), final_ace.number());
             }
 
         }
@@ -228,30 +328,27 @@ impl<'a> Game<'a> {
 
         //Table
         for (stack_index, table_stack) in self.table.iter().enumerate() {
-            match table_stack.upturned.last() {
-                Some(final_card) => {
-                    if let Some(latest_ace_num) = aces.get(&final_card.suit) {
-                        if final_card.number == latest_ace_num + 1 {
+            if let Some(final_card) = table_stack.upturned.last() {
+                if let Some(latest_ace_num) = aces.get(&final_card.suit()) {
+                    if final_card.number() == latest_ace_num + 1 {
 
-                            moves.push(GameMove {
-                                from: CardPosition::TableUpturned { stack_index: stack_index as i8, upturned_index: (table_stack.upturned.len()-1) as i8 },
-                                to: CardPosition::Ace { suit_index: final_card.suit_index }
-                            });
+                        moves.push(GameMove {
+                            from: CardPosition::TableUpturned { stack_index: stack_index as i8, upturned_index: (table_stack.upturned.len()-1) as i8 },
+                            to: CardPosition::Ace { suit_index: final_card.suit_index() }
+                        });
 
-                        }
                     }
-                },
-                None => (),
+                }
             }
         }
 
         //Draw
-        for (deck_index, draw_card) in self.draw.iter().enumerate() {
-            if let Some(latest_ace_num) = aces.get(&draw_card.suit) {
-                if draw_card.number == latest_ace_num + 1 {
+        for (draw_card, draw_position) in self.get_playable_draw_cards() {
+            if let Some(latest_ace_num) = aces.get(&draw_card.suit()) {
+                if draw_card.number() == latest_ace_num + 1 {
                     moves.push(GameMove {
-                        from: CardPosition::DrawDeck { deck_index: deck_index as i8 },
-                        to: CardPosition::Ace { suit_index: draw_card.suit_index }
+                        from: draw_position,
+                        to: CardPosition::Ace { suit_index: draw_card.suit_index() }
                     })
                 }
             }
@@ -271,15 +368,15 @@ impl<'a> Game<'a> {
                 for (compare_card, compare_position) in final_cards {
 
                     //If they're different colour, consecutive numbers, its a move
-                    if !upturned_card.suit.same_color(compare_card.suit) {
-                        if compare_card.number == upturned_card.number + 1 {
+                    if !upturned_card.suit().same_color(compare_card.suit())
+                        && compare_card.number() == upturned_card.number() + 1
+                    {
 
-                            moves.push(GameMove {
-                                from: CardPosition::TableUpturned { stack_index: stack_index as i8, upturned_index: upturned_index as i8 },
-                                to: compare_position.clone()
-                            });
+                        moves.push(GameMove {
+                            from: CardPosition::TableUpturned { stack_index: stack_index as i8, upturned_index: upturned_index as i8 },
+                            to: compare_position.clone()
+                        });
 
-                        }
                     }
                 }
             }
@@ -292,24 +389,67 @@ impl<'a> Game<'a> {
         let mut moves = Vec::new();
 
         //Find the moves
-        for (draw_index, draw_card) in self.draw.iter().enumerate() {
+        for (draw_card, draw_position) in self.get_playable_draw_cards() {
 
             //For each draw card, compare it against the final cards of all the stacks
             for (compare_card, compare_position) in final_cards {
 
 
                 //If they're different colour, consecutive numbers, its a move
-                if !draw_card.suit.same_color(compare_card.suit) {
-                    if compare_card.number == draw_card.number + 1 {
+                if !draw_card.suit().same_color(compare_card.suit())
+                    && compare_card.number() == draw_card.number() + 1
+                {
+
+                    moves.push(GameMove {
+                        from: draw_position.clone(),
+                        to: compare_position.clone()
+                    });
+
+                }
+
+            }
+        }
+
+        moves
+    }
+
+
+    pub fn get_foundation_moves(&self, final_cards: &Vec<(&Card, CardPosition)>) -> Vec<GameMove> {
+        //Moves from the top of the ace stacks back down to the table - none once the game is won
+        let mut moves = Vec::new();
+        if self.aces.iter().all(|ace_stack| ace_stack.is_full()) {
+            return moves;
+        }
+
+        let vacant_stack_index = self.table.iter()
+            .position(|table_stack| table_stack.upturned.is_empty() && table_stack.downturned.is_empty());
 
+        for ace_stack in &self.aces {
+            if let Some(ace_card) = ace_stack.ace_stack.last() {
+                let from = CardPosition::Ace { suit_index: ace_card.suit_index() };
+
+                //Kings can only come down into a vacant stack
+                if ace_card.number() == 13 {
+                    if let Some(vacant_stack_index) = vacant_stack_index {
                         moves.push(GameMove {
-                            from: CardPosition::DrawDeck { deck_index: draw_index as i8 },
-                            to: compare_position.clone()
+                            from,
+                            to: CardPosition::TableUpturned { stack_index: vacant_stack_index as i8, upturned_index: 0 }
                         });
-
                     }
+                    continue
                 }
 
+                //For each ace card, compare it against the final cards of all the stacks
+                for (compare_card, compare_position) in final_cards {
+
+                    //If they're different colour, consecutive numbers, its a move
+                    if !ace_card.suit().same_color(compare_card.suit()) && compare_card.number() == ace_card.number() + 1 {
+                        moves.push(GameMove {
+                            from: from.clone(),
+                            to: compare_position.clone()
+                        });
+                    }
+                }
             }
         }
 
@@ -317,25 +457,22 @@ impl<'a> Game<'a> {
     }
 
 
-    pub fn get_game_state(&self) -> GameState {
+    pub fn get_game_state(&self) -> GameState<'_> {
 
         //Gather the last upturned cards of each stack to potentially move a stack onto
         let mut final_table_stack_cards: Vec<(&Card, CardPosition)> = Vec::with_capacity(4); //There is always at least 4 stacks
         for (stack_index, table_stack) in self.table.iter().enumerate() {
-            match table_stack.upturned.last() {
-                Some(final_card) => {
+            if let Some(final_card) = table_stack.upturned.last() {
 
-                    final_table_stack_cards.push((
-                        final_card,
-                        CardPosition::TableUpturned {
-                            stack_index: stack_index as i8,
-                            upturned_index: (table_stack.upturned.len()) as i8 //Dont -1 because this is the position  we're placing onto, after the current card
-                        }
-                    ))
+                final_table_stack_cards.push((
+                    final_card,
+                    CardPosition::TableUpturned {
+                        stack_index: stack_index as i8,
+                        upturned_index: (table_stack.upturned.len()) as i8 //Dont -1 because this is the position  we're placing onto, after the current card
+                    }
+                ))
 
 
-                },
-                None => (),
             }
         }
 
@@ -350,6 +487,8 @@ impl<'a> Game<'a> {
             ace_stack_moves: self.get_ace_stack_moves(),
             table_moves: self.get_table_moves(&final_table_stack_cards),
             deck_moves: self.get_deck_moves(&final_table_stack_cards),
+            foundation_moves: self.get_foundation_moves(&final_table_stack_cards),
+            stock_moves: self.get_stock_moves(),
 
 
             aces: &self.aces,
@@ -362,18 +501,60 @@ impl<'a> Game<'a> {
 
 
 
-#[derive(Debug)]
-pub struct AceStack<'a> {
-    pub ace_stack: Vec<Card<'a>>
+#[derive(Debug, Eq, PartialEq)]
+pub enum DeckError {
+    CardOutOfRange(i8), //Card indices are 0..52
+    DuplicateCard(i8), //With 52 slots, a duplicate also means another card is missing
 }
-impl<'a> AceStack<'a> {
+
+impl Display for DeckError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeckError::CardOutOfRange(card_index) => write!(f, "card index {} is not in 0..52", card_index),
+            DeckError::DuplicateCard(card_index) => write!(f, "card index {} appears more than once", card_index),
+        }
+    }
+}
+
+impl std::error::Error for DeckError {}
+
+
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct AceStack {
+    pub ace_stack: Pile<13>
+}
+impl AceStack {
     pub fn is_full(&self) -> bool { self.ace_stack.len() >= 13 }
 }
 
 
 
-#[derive(Debug)]
-pub struct TableStack<'a> {
-    pub downturned: Vec<Card<'a>>,
-    pub upturned: Vec<Card<'a>>
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct TableStack {
+    pub downturned: Pile<6>, //At most the six dealt under the last stack
+    pub upturned: Pile<13> //An alternating run can only hold each number once
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seed_deals_the_same_game() {
+        let game: Game = "
+            foundation H:0 D:0 C:0 S:0
+            stock AC JC TC 6S TH 8C 4H 5C 9D 2H 9C 7H 4C 7D AS KH QC TS 6D KS JH JS 8D 3C
+            waste
+            1: 3S
+            2: Jd 3D
+            3: 3h 5h AD
+            4: Qd 5s 7s 8H
+            5: 4s 4d 8s 5d 2D
+            6: 2s Kd Td 2c 6h AH
+            7: Qs 7c Qh 9h 6c Kc 9S
+        ".parse().unwrap();
+
+        assert!(Game::from_seed(1) == game, "seed 1 dealt\n{}", Game::from_seed(1));
+    }
 }
@@ -0,0 +1,89 @@
+use crate::json;
+use crate::json::Value;
+use crate::solitare::card::Card;
+use crate::solitare::layout::{downturned_token, Parts};
+use crate::solitare::solitare::Game;
+
+//Solvitaire's JSON layout for Klondike:
+//  {"tableau piles": [["9s", "KH"], ...], "foundation": [["AH", "2H"], [], [], []], "stock": [...], "waste": [...]}
+//Piles list their bottom card first, so the last card of the stock is drawn next and the last of the waste is playable.
+//A lowercase suit marks a downturned card. Foundation piles may come in any order, and with foundation, stock and waste
+//left out when empty. Solvitaire takes the draw mode on its command line rather than in the layout, so it isn't written
+//here and an imported Game has the default rules
+pub fn to_solvitaire(game: &Game) -> String {
+    let cards = |cards: &mut dyn Iterator<Item = String>| -> String {
+        format!("[{}]", cards.map(|token| json::string(&token)).collect::<Vec<_>>().join(", "))
+    };
+
+    let tableau: Vec<String> = game.table.iter()
+        .map(|table_stack| cards(&mut table_stack.downturned.iter().map(downturned_token).chain(table_stack.upturned.iter().map(Card::to_string))))
+        .collect();
+    let foundation: Vec<String> = game.aces.iter()
+        .map(|ace_stack| cards(&mut ace_stack.ace_stack.iter().map(Card::to_string)))
+        .collect();
+
+    format!(
+        "{{\n  \"tableau piles\": [\n    {}\n  ],\n  \"foundation\": [{}],\n  \"stock\": {},\n  \"waste\": {}\n}}\n",
+        tableau.join(",\n    "),
+        foundation.join(", "),
+        cards(&mut game.draw.iter().map(Card::to_string)),
+        cards(&mut game.waste.iter().map(Card::to_string)),
+    )
+}
+
+pub fn from_solvitaire(s: &str) -> Result<Game, String> {
+    let layout = json::parse(s)?;
+    let mut parts = Parts::new();
+
+    //Every pile is an array of card tokens
+    let pile = |value: &Value| -> Result<Vec<(Card, bool)>, String> {
+        value.as_array().ok_or("expected an array of cards")?.iter()
+            .map(|token| {
+                let token = token.as_str().ok_or("expected a card")?;
+                Ok((token.parse()?, token.ends_with(|c: char| c.is_ascii_lowercase())))
+            })
+            .collect()
+    };
+    let upturned_pile = |value: Option<&Value>| -> Result<Vec<Card>, String> {
+        match value {
+            Some(value) => Ok(pile(value)?.into_iter().map(|(card, _)| card).collect()),
+            None => Ok(Vec::new()),
+        }
+    };
+
+    let tableau = layout.get("tableau piles").and_then(Value::as_array).ok_or("no \"tableau piles\" array")?;
+    if tableau.len() != 7 {
+        return Err(format!("Klondike has 7 tableau piles, not {}", tableau.len()));
+    }
+    for (stack_index, tableau_pile) in tableau.iter().enumerate() {
+        let (downturned, upturned) = &mut parts.table[stack_index];
+        for (card, is_downturned) in pile(tableau_pile)? {
+            if !is_downturned {
+                upturned.push(card);
+            } else if upturned.is_empty() {
+                downturned.push(card);
+            } else {
+                return Err(format!("downturned {} is on top of upturned cards", card));
+            }
+        }
+    }
+
+    if let Some(foundation) = layout.get("foundation") {
+        for foundation_pile in foundation.as_array().ok_or("expected \"foundation\" to be an array")? {
+            let cards = upturned_pile(Some(foundation_pile))?;
+            if let Some(first_card) = cards.first() {
+                let in_sequence = cards.iter().enumerate()
+                    .all(|(card_index, card)| card.suit_index() == first_card.suit_index() && card.number() as usize == card_index + 1);
+                if !in_sequence {
+                    return Err(format!("foundation {:?} isn't a single suit ascending from the ace", cards));
+                }
+                parts.foundation_heights[first_card.suit_index() as usize] = cards.len() as i8;
+            }
+        }
+    }
+
+    parts.draw = upturned_pile(layout.get("stock"))?;
+    parts.waste = upturned_pile(layout.get("waste"))?;
+
+    parts.into_game()
+}
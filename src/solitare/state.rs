@@ -1,4 +1,7 @@
-use crate::solitare::card::Card;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use crate::solitare::apply::apply;
+use crate::solitare::card::{suit_index_of_letter, Card, SUIT_LETTERS};
 use crate::solitare::solitare::{AceStack, Game};
 
 #[derive(Debug)]
@@ -10,33 +13,30 @@ pub struct GameState<'a> { //Describes all the possible moves of the game at its
     pub ace_stack_moves: Vec<GameMove>,
     pub table_moves: Vec<GameMove>,
     pub deck_moves: Vec<GameMove>,
+    pub foundation_moves: Vec<GameMove>, //Taking the top card of an ace stack back down to the table
+    pub stock_moves: Vec<GameMove>, //Turning the stock onto the waste, or recycling the waste
 
-    pub aces: &'a[AceStack<'a>; 4],
+    pub aces: &'a[AceStack; 4],
     pub queuing_kings: i8,
 }
 
 impl<'a> GameState<'a> {
     pub fn get_final_state(&self) -> GameFinalState {
 
-        if self.table_flip_moves.len() == 0
-            && self.table_ace_moves.len() == 0
-            && self.draw_ace_moves.len() == 0
-            && self.table_king_moves.len() == 0
-            && self.ace_stack_moves.len() == 0
-            && self.table_moves.len() == 0
-            && self.deck_moves.len() == 0
+        //A finished board is won whatever moves it still offers
+        if self.aces.iter().all(|ace_stack| ace_stack.is_full()) {
+            GameFinalState::WON
+        } else if self.table_flip_moves.is_empty()
+            && self.table_ace_moves.is_empty()
+            && self.draw_ace_moves.is_empty()
+            && self.table_king_moves.is_empty()
+            && self.ace_stack_moves.is_empty()
+            && self.table_moves.is_empty()
+            && self.deck_moves.is_empty()
+            && self.foundation_moves.is_empty()
+            && self.stock_moves.is_empty()
         {
-
-            if self.aces[0].is_full()
-                && self.aces[1].is_full()
-                && self.aces[2].is_full()
-                && self.aces[3].is_full()
-            {
-                GameFinalState::WON
-            } else {
-                GameFinalState::LOST
-            }
-
+            GameFinalState::LOST
         } else {
 
             GameFinalState::UNFINISHED
@@ -44,7 +44,7 @@ impl<'a> GameState<'a> {
         }
     }
 
-    //Function exists only for debugging
+    //Every move in priority order - used for debugging and by the search solver
     pub fn get_all_moves_youch(&self) -> Vec<&GameMove> {
         let mut moves = Vec::new();
 
@@ -55,43 +55,153 @@ impl<'a> GameState<'a> {
         moves.extend(&self.ace_stack_moves);
         moves.extend(&self.table_moves);
         moves.extend(&self.deck_moves);
+        moves.extend(&self.foundation_moves);
+        moves.extend(&self.stock_moves);
 
         moves
     }
 }
 
 
-#[derive(Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum GameFinalState {
     WON,
     LOST,
     UNFINISHED
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub enum CardPosition {
     TableDownturned { stack_index: i8, downturned_index: i8 },
     TableUpturned { stack_index: i8, upturned_index: i8 },
     Ace { suit_index: i8 },
-    DrawDeck { deck_index: i8 }
+    DrawDeck { deck_index: i8 }, //Any stock card, in open draw mode
+    Waste, //Top card of the waste
+    Stock, //Only used by the moves turning the stock over: Stock -> Waste draws, Waste -> Stock recycles
 }
 
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct GameMove {
     pub from: CardPosition,
     pub to: CardPosition,
 }
 
+//Move notation - stacks and cards are counted from 1, bottom first, the same as the layout format:
+//  t3.2      the 2nd upturned card of table stack 3 (and everything on it)
+//  d3.2      the 2nd downturned card of table stack 3
+//  fH        the ace stack of hearts (D, C, S for the others)
+//  s12       the 12th card of the stock, open draw only
+//  w         the top card of the waste
+//A move is its two positions, like t3.2-t5.4, t1.1-fS or w-t2.3, except:
+//  flip d6.3     turn over the last downturned card of a stack
+//  draw          turn cards from the stock onto the waste
+//  recycle       turn the waste back over into the stock
+impl Display for CardPosition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            CardPosition::TableDownturned { stack_index, downturned_index } => write!(f, "d{}.{}", stack_index + 1, downturned_index + 1),
+            CardPosition::TableUpturned { stack_index, upturned_index } => write!(f, "t{}.{}", stack_index + 1, upturned_index + 1),
+            CardPosition::Ace { suit_index } => write!(f, "f{}", SUIT_LETTERS.get(suit_index as usize).copied().unwrap_or('?')),
+            CardPosition::DrawDeck { deck_index } => write!(f, "s{}", deck_index + 1),
+            CardPosition::Waste => write!(f, "w"),
+            CardPosition::Stock => write!(f, "s"),
+        }
+    }
+}
+
+impl FromStr for CardPosition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad_position = || format!("bad position '{}'", s);
+        //Counted from 1 in notation, from 0 in a CardPosition
+        let index = |digits: &str| -> Result<i8, String> {
+            match digits.parse::<i8>() {
+                Ok(number) if number >= 1 => Ok(number - 1),
+                _ => Err(bad_position()),
+            }
+        };
+
+        if !s.is_ascii() {
+            return Err(bad_position());
+        }
+
+        match s.split_at(s.len().min(1)) {
+            ("w", "") => Ok(CardPosition::Waste),
+            ("s", "") => Ok(CardPosition::Stock),
+            ("s", digits) => Ok(CardPosition::DrawDeck { deck_index: index(digits)? }),
+            ("f", suit_letter) => {
+                let suit_index = suit_index_of_letter(suit_letter).ok_or_else(bad_position)?;
+                Ok(CardPosition::Ace { suit_index: suit_index as i8 })
+            },
+            ("t", digits) => {
+                let (stack_number, card_number) = digits.split_once('.').ok_or_else(bad_position)?;
+                Ok(CardPosition::TableUpturned { stack_index: index(stack_number)?, upturned_index: index(card_number)? })
+            },
+            ("d", digits) => {
+                let (stack_number, card_number) = digits.split_once('.').ok_or_else(bad_position)?;
+                Ok(CardPosition::TableDownturned { stack_index: index(stack_number)?, downturned_index: index(card_number)? })
+            },
+            _ => Err(bad_position()),
+        }
+    }
+}
+
+impl Display for GameMove {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (&self.from, &self.to) {
+            (CardPosition::Stock, CardPosition::Waste) => write!(f, "draw"),
+            (CardPosition::Waste, CardPosition::Stock) => write!(f, "recycle"),
+            (CardPosition::TableDownturned { .. }, _) => write!(f, "flip {}", self.from),
+            (from, to) => write!(f, "{}-{}", from, to),
+        }
+    }
+}
+
+//Only reads the notation - whether the move is legal is up to apply
+impl FromStr for GameMove {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        match s {
+            "draw" => return Ok(GameMove { from: CardPosition::Stock, to: CardPosition::Waste }),
+            "recycle" => return Ok(GameMove { from: CardPosition::Waste, to: CardPosition::Stock }),
+            _ => (),
+        }
+
+        if let Some(position) = s.strip_prefix("flip ") {
+            return match position.trim().parse()? {
+                //Flipping turns the card over where it lies, as the first upturned card
+                from @ CardPosition::TableDownturned { stack_index, .. } => Ok(GameMove { from, to: CardPosition::TableUpturned { stack_index, upturned_index: 0 } }),
+                _ => Err(format!("only downturned cards can be flipped, not '{}'", position)),
+            };
+        }
+
+        match s.split_once('-') {
+            Some((from, to)) => Ok(GameMove { from: from.trim().parse()?, to: to.trim().parse()? }),
+            None => Err(format!("bad move '{}'", s)),
+        }
+    }
+}
+
 impl GameMove {
     //Function exists only for debugging
     pub fn debug_move(&self, game: &Game) -> String {
         let mut line2 = String::from("\n");
 
+        match (&self.from, &self.to) {
+            (CardPosition::Stock, CardPosition::Waste) => return format!("Drawing from stock {:?}", game.draw),
+            (CardPosition::Waste, CardPosition::Stock) => return format!("Recycling waste {:?}", game.waste),
+            _ => (),
+        }
+
         let mut cards: Vec<&Card>;
-        match *&self.from {
+        match self.from {
             CardPosition::TableDownturned { stack_index, downturned_index } => {
-                assert!(stack_index < 8);
+                assert!(stack_index < 7);
 
                 let stack = &game.table[stack_index as usize];
                 line2 += &*format!(" From stack {:?}", stack);
@@ -112,17 +222,26 @@ impl GameMove {
             CardPosition::Ace { suit_index } => {
                 assert!(suit_index < 4);
 
-                unreachable!("There should be no moves FROM Ace");
+                let ace_stack = &game.aces[suit_index as usize];
+                line2 += &*format!(" From ace {:?}", ace_stack);
+
+                cards = ace_stack.ace_stack.last().into_iter().collect();
             },
             CardPosition::DrawDeck { deck_index } => {
                 assert!(deck_index < 24);
 
                 cards = vec![&game.draw[deck_index as usize]];
             },
+            CardPosition::Waste => {
+                cards = game.waste.last().into_iter().collect();
+            },
+            CardPosition::Stock => {
+                unreachable!("There should be no moves FROM Stock other than drawing");
+            },
         }
 
         //Add to 'to'
-        match *&self.to {
+        match self.to {
             CardPosition::TableUpturned { stack_index, upturned_index } => {
                 assert!(stack_index < 7);
                 let stack = &game.table[stack_index as usize];
@@ -131,90 +250,19 @@ impl GameMove {
                 if let Some(onto_card) = stack.upturned.get((upturned_index-1) as usize) {
                     return format!("Moving {:?} onto [{:?}]\n{}", cards, onto_card, line2);
                 }
-                return format!("Moving {:?}", cards);
+                format!("Moving {:?}", cards)
             },
             CardPosition::Ace { suit_index } => {
-                return format!("Moving {:?} to ace [{}]\n{}", cards, suit_index, line2);
+                format!("Moving {:?} to ace [{}]\n{}", cards, suit_index, line2)
             },
             _ => {
-                return format!("Moving {:?}\n{}", cards, line2);
+                format!("Moving {:?}\n{}", cards, line2)
             },
         }
     }
 
+    //For moves the solvers generated themselves - anything from outside should go through apply
     pub fn execute(&self, game: &mut Box<Game>) {
-
-        //Fetch & remove from 'from'
-        let mut cards: Vec<Card>;
-
-        match *&self.from {
-            CardPosition::TableDownturned { stack_index, downturned_index } => {
-                assert!(stack_index < 8);
-
-                let stack = &mut game.table[stack_index as usize];
-                cards = vec![stack.downturned.remove(downturned_index as usize)];
-            },
-            CardPosition::TableUpturned { stack_index, upturned_index } => {
-                assert!(stack_index < 7);
-
-                let stack = &mut game.table[stack_index as usize];
-                cards = stack.upturned.drain((upturned_index as usize)..).collect();
-            },
-            CardPosition::Ace { suit_index } => {
-                assert!(suit_index < 4);
-
-                unreachable!("There should be no moves FROM Ace");
-            },
-            CardPosition::DrawDeck { deck_index } => {
-                assert!(deck_index < 24);
-
-                cards = vec![game.draw.remove(deck_index as usize)];
-            },
-        }
-        println!(" - - Executing move {:?}", cards);
-
-        //Add to 'to'
-        match *&self.to {
-            CardPosition::TableDownturned { stack_index, .. } => {
-                assert!(stack_index < 7);
-
-                unreachable!("There should be no moves TO TableDownturned")
-            },
-            CardPosition::TableUpturned { stack_index, upturned_index } => {
-                assert!(stack_index < 7);
-                let stack = &mut game.table[stack_index as usize];
-                let upturned_index_usize = upturned_index as usize;
-
-                stack.upturned.splice(upturned_index_usize..upturned_index_usize, cards);
-
-                if let Some(onto_card) = stack.upturned.get((upturned_index-1) as usize) {
-                    println!(" - - - Onto [{:?}]", onto_card);
-                }
-            },
-            CardPosition::Ace { suit_index } => {
-                assert!(suit_index < 4);
-
-                //Can only move 1 card to the ace (at a time)
-                assert_eq!(cards.len(), 1);
-                let card = cards.remove(0);
-
-                let ace_stack = &mut game.aces[suit_index as usize];
-
-                //Check the last element's number is our number - 1
-                match ace_stack.ace_stack.last() {
-                    Some(last) => {
-                        assert_eq!(last.number, card.number - 1);
-                    },
-                    None => (),
-                };
-
-                ace_stack.ace_stack.push(card);
-            },
-            CardPosition::DrawDeck { deck_index } => {
-                assert!(deck_index < 28);
-
-                unreachable!("There should be no moves TO DrawDeck")
-            },
-        }
+        apply(game, self).expect("Generated moves are always legal");
     }
 }
\ No newline at end of file
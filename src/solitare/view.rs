@@ -0,0 +1,115 @@
+use rand::prelude::SliceRandom;
+use rand::Rng;
+use crate::solitare::card::Card;
+use crate::solitare::pile::Pile;
+use crate::solitare::rules::{DrawMode, Rules};
+use crate::solitare::solitare::{AceStack, Game, TableStack};
+
+//What a player sitting at the table can see of a Game - downturned cards are hidden, as are stock and waste cards
+//not yet turned up. Cards once turned from the stock keep their place in the stock/waste cycle, so they are remembered
+#[derive(Clone, Debug)]
+pub struct GameView {
+    pub table: [TableStackView; 7],
+    pub aces: [AceStack; 4],
+    pub draw: Vec<Option<Card>>, //Stock cards, known if seen before - all known in open draw mode
+    pub waste: Vec<Option<Card>>, //Waste cards, known if seen before - the top is always known
+    pub rules: Rules,
+    pub redeals_used: u8,
+
+    //Belief over the hidden cards - every card not known anywhere, each equally likely to be in any hidden slot
+    pub unseen: Vec<Card>,
+}
+
+#[derive(Clone, Debug)]
+pub struct TableStackView {
+    pub downturned_len: usize,
+    pub upturned: Pile<13>,
+}
+
+impl GameView {
+    pub fn new(game: &Game) -> Self {
+        Self::remembering(game, &[false; 52])
+    }
+
+    //seen marks the cards the player has watched being turned from the stock (by card index)
+    pub fn remembering(game: &Game, seen: &[bool; 52]) -> Self {
+        let open_draw = game.rules.draw == DrawMode::Open;
+        let is_seen = |card: &Card| seen[card.index()];
+
+        let table = core::array::from_fn(|stack_index| {
+            let table_stack = &game.table[stack_index];
+            TableStackView {
+                downturned_len: table_stack.downturned.len(),
+                upturned: table_stack.upturned,
+            }
+        });
+
+        let draw = game.draw.iter()
+            .map(|card| if open_draw || is_seen(card) { Some(*card) } else { None })
+            .collect();
+
+        let waste_len = game.waste.len();
+        let waste = game.waste.iter().enumerate()
+            .map(|(waste_index, card)| if waste_index + 1 == waste_len || is_seen(card) { Some(*card) } else { None })
+            .collect();
+
+        let mut view = Self {
+            table,
+            aces: game.aces,
+            draw,
+            waste,
+            rules: game.rules,
+            redeals_used: game.redeals_used,
+            unseen: Vec::new(),
+        };
+
+        //Work out the unseen cards from what's known, never from the hidden cards themselves
+        let mut known = [false; 52];
+        let mut mark = |card: &Card| known[card.index()] = true;
+        for table_stack in &view.table {
+            table_stack.upturned.iter().for_each(&mut mark);
+        }
+        for ace_stack in &view.aces {
+            ace_stack.ace_stack.iter().for_each(&mut mark);
+        }
+        view.draw.iter().chain(&view.waste).flatten().for_each(&mut mark);
+
+        view.unseen = (0..52).filter(|&card_index| !known[card_index as usize]).map(Card::new).collect();
+
+        view
+    }
+
+    //A full Game consistent with everything known, with the hidden slots dealt at random from the unseen cards
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Game {
+        let mut unseen = self.unseen.clone();
+        unseen.shuffle(rng);
+
+        let table = core::array::from_fn(|stack_index| {
+            let table_stack = &self.table[stack_index];
+            TableStack {
+                downturned: Pile::from_slice(&unseen.split_off(unseen.len() - table_stack.downturned_len)),
+                upturned: table_stack.upturned,
+            }
+        });
+
+        let mut fill = |cards: &Vec<Option<Card>>| -> Pile<24> {
+            let mut pile = Pile::new();
+            for card in cards {
+                pile.push(card.unwrap_or_else(|| unseen.pop().expect("Every hidden slot has an unseen card")));
+            }
+            pile
+        };
+        let draw = fill(&self.draw);
+        let waste = fill(&self.waste);
+        assert!(unseen.is_empty(), "Every unseen card fills exactly one hidden slot");
+
+        Game {
+            table,
+            aces: self.aces,
+            draw,
+            waste,
+            rules: self.rules,
+            redeals_used: self.redeals_used,
+        }
+    }
+}
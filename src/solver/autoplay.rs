@@ -0,0 +1,44 @@
+use crate::solitare::apply::apply;
+use crate::solitare::card::{Card, Suit};
+use crate::solitare::rules::DrawMode;
+use crate::solitare::solitare::Game;
+use crate::solitare::state::{CardPosition, GameMove};
+
+//A card can safely go up once both ace stacks of the other colour hold the card below it - no card left on the
+//table could then need it to go onto. Aces and twos are always safe
+pub fn is_safe_to_put_up(game: &Game, card: Card) -> bool {
+    card.number() <= 2 || game.aces.iter().enumerate()
+        .filter(|(suit_index, _)| !Suit::from_index(*suit_index as i8).same_color(card.suit()))
+        .all(|(_, ace_stack)| ace_stack.ace_stack.len() as i8 >= card.number() - 1)
+}
+
+//A move putting a card up that can never hurt, so solvers make it without considering anything else. From the
+//end of a table stack, or from the stock in one and open draw - in three draw, taking a card out of the waste
+//shifts which cards every later pass turns up, which can bury a card that was reachable
+pub fn safe_foundation_move(game: &Game) -> Option<GameMove> {
+    let table_cards = game.table.iter().enumerate().filter_map(|(stack_index, table_stack)| {
+        table_stack.upturned.last().map(|card| (*card, CardPosition::TableUpturned {
+            stack_index: stack_index as i8,
+            upturned_index: table_stack.upturned.len() as i8 - 1,
+        }))
+    });
+    let draw_cards = match game.rules.draw {
+        DrawMode::Open | DrawMode::One => game.get_playable_draw_cards().into_iter().map(|(card, position)| (*card, position)).collect(),
+        DrawMode::Three => Vec::new(),
+    };
+
+    table_cards.chain(draw_cards)
+        .find(|&(card, _)| {
+            let ace_stack = &game.aces[card.suit_index() as usize];
+            ace_stack.ace_stack.len() as i8 == card.number() - 1 && is_safe_to_put_up(game, card)
+        })
+        .map(|(card, from)| GameMove { from, to: CardPosition::Ace { suit_index: card.suit_index() } })
+}
+
+//Makes every safe foundation move there is, recording them - each card put up can make the next one safe
+pub fn autoplay(game: &mut Game, moves: &mut Vec<GameMove>) {
+    while let Some(game_move) = safe_foundation_move(game) {
+        apply(game, &game_move).expect("Safe foundation moves are always legal");
+        moves.push(game_move);
+    }
+}
@@ -0,0 +1,153 @@
+use std::io::Write;
+use std::ops::Range;
+use std::time::{Duration, Instant};
+use log::{error, info};
+use crate::json;
+use crate::solitare::rules::Rules;
+use crate::solitare::solitare::Game;
+use crate::solitare::state::GameFinalState;
+use crate::solver::strategy::StrategyKind;
+use crate::solver::verify::verify;
+use crate::solver::{pool, SolveResult, SolverKind};
+
+//Solves many seeded deals with one solver, to measure how often it wins
+pub struct Batch {
+    pub solver: SolverKind,
+    pub strategy: StrategyKind, //For the greedy solver
+    pub rules: Rules,
+    pub max_nodes: usize,
+    pub threads: usize, //Shared out over the deals, or given to the parallel solver while it solves them one at a time
+}
+
+//How one deal went
+#[derive(Clone, Debug)]
+pub struct DealReport {
+    pub seed: u64,
+    pub outcome: GameFinalState, //UNFINISHED when the solver was stuck or ran out of budget
+    pub reason: Option<String>, //Why an UNFINISHED deal was left unfinished
+    pub moves: usize, //Moves of the win, 0 otherwise
+    pub nodes: usize,
+    pub elapsed: Duration,
+}
+
+pub struct BatchReport {
+    pub deals: Vec<DealReport>, //In seed order
+    pub elapsed: Duration, //Wall time of the whole batch
+}
+
+#[derive(Clone, Debug)]
+pub struct BatchSummary {
+    pub deals: usize,
+    pub won: usize,
+    pub lost: usize,
+    pub stuck: usize,
+    pub average_moves: f64, //Over won deals
+    pub nodes: usize,
+    pub average_nodes: f64,
+    pub solve_time: Duration, //Summed over deals - more than the wall time when run across threads
+    pub elapsed: Duration,
+}
+
+impl Batch {
+    pub fn new(solver: SolverKind, rules: Rules, max_nodes: usize) -> Self {
+        Self { solver, strategy: StrategyKind::default(), rules, max_nodes, threads: 1 }
+    }
+
+    pub fn run(&self, seeds: Range<u64>) -> BatchReport {
+        let start = Instant::now();
+        let seeds: Vec<u64> = seeds.collect();
+        let deal_threads = if self.solver == SolverKind::Parallel { 1 } else { self.threads };
+        let deals = pool::map(&seeds, deal_threads, |&seed| self.solve_deal(seed));
+
+        BatchReport { deals, elapsed: start.elapsed() }
+    }
+
+    pub fn solve_deal(&self, seed: u64) -> DealReport {
+        let start = Instant::now();
+        let game = Game::from_seed(seed).with_rules(self.rules);
+        let (result, nodes) = self.solver.solve_counting_with(&game, self.max_nodes, self.strategy, self.threads);
+
+        let (outcome, reason, moves) = match result {
+            //Wins only count once they've been replayed
+            SolveResult::Won(moves) => match verify(&game, &moves) {
+                Ok(_) => (GameFinalState::WON, None, moves.len()),
+                Err(verify_error) => {
+                    error!("Deal {} {} solution failed verification: {}", seed, self.solver, verify_error);
+                    (GameFinalState::UNFINISHED, Some(format!("solution failed verification: {}", verify_error)), 0)
+                },
+            },
+            SolveResult::Lost => (GameFinalState::LOST, None, 0),
+            SolveResult::Stuck(reason) => (GameFinalState::UNFINISHED, Some(reason), 0),
+            SolveResult::BudgetExceeded => (GameFinalState::UNFINISHED, Some(String::from("budget exceeded")), 0),
+        };
+        info!("Deal {} {:?} in {:?}", seed, outcome, start.elapsed());
+
+        DealReport { seed, outcome, reason, moves, nodes, elapsed: start.elapsed() }
+    }
+}
+
+impl BatchReport {
+    pub fn summary(&self) -> BatchSummary {
+        let count = |outcome: GameFinalState| self.deals.iter().filter(|deal| deal.outcome == outcome).count();
+        let won = count(GameFinalState::WON);
+        let won_moves: usize = self.deals.iter().filter(|deal| deal.outcome == GameFinalState::WON).map(|deal| deal.moves).sum();
+        let nodes: usize = self.deals.iter().map(|deal| deal.nodes).sum();
+
+        BatchSummary {
+            deals: self.deals.len(),
+            won,
+            lost: count(GameFinalState::LOST),
+            stuck: count(GameFinalState::UNFINISHED),
+            average_moves: if won == 0 { 0.0 } else { won_moves as f64 / won as f64 },
+            nodes,
+            average_nodes: if self.deals.is_empty() { 0.0 } else { nodes as f64 / self.deals.len() as f64 },
+            solve_time: self.deals.iter().map(|deal| deal.elapsed).sum(),
+            elapsed: self.elapsed,
+        }
+    }
+
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writeln!(writer, "seed,outcome,reason,moves,nodes,millis")?;
+        for deal in &self.deals {
+            //Reasons are free text - quote them, doubling any quotes inside
+            let reason = deal.reason.as_deref().map(|reason| format!("\"{}\"", reason.replace('"', "\"\""))).unwrap_or_default();
+            writeln!(writer, "{},{},{},{},{},{:.3}", deal.seed, outcome_name(deal.outcome), reason, deal.moves, deal.nodes, deal.elapsed.as_secs_f64() * 1000.0)?;
+        }
+        Ok(())
+    }
+
+    pub fn write_json<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writeln!(writer, "{{\"summary\": {},", self.summary().to_json())?;
+        writeln!(writer, " \"deals\": [")?;
+        for (deal_index, deal) in self.deals.iter().enumerate() {
+            let separator = if deal_index + 1 == self.deals.len() { "" } else { "," };
+            writeln!(
+                writer,
+                "  {{\"seed\": {}, \"outcome\": \"{}\", \"reason\": {}, \"moves\": {}, \"nodes\": {}, \"millis\": {:.3}}}{}",
+                deal.seed, outcome_name(deal.outcome), json::optional_string(deal.reason.as_deref()), deal.moves, deal.nodes, deal.elapsed.as_secs_f64() * 1000.0, separator
+            )?;
+        }
+        writeln!(writer, " ]}}")
+    }
+}
+
+impl BatchSummary {
+    pub fn win_rate(&self) -> f64 {
+        if self.deals == 0 { 0.0 } else { self.won as f64 / self.deals as f64 }
+    }
+
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"deals\": {}, \"won\": {}, \"lost\": {}, \"stuck\": {}, \"win_rate\": {:.4}, \"average_moves\": {:.1}, \"nodes\": {}, \"average_nodes\": {:.1}, \"solve_seconds\": {:.3}, \"wall_seconds\": {:.3}}}",
+            self.deals, self.won, self.lost, self.stuck, self.win_rate(), self.average_moves, self.nodes, self.average_nodes, self.solve_time.as_secs_f64(), self.elapsed.as_secs_f64()
+        )
+    }
+}
+
+fn outcome_name(outcome: GameFinalState) -> &'static str {
+    match outcome {
+        GameFinalState::WON => "won",
+        GameFinalState::LOST => "lost",
+        GameFinalState::UNFINISHED => "stuck",
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::{HashMap, HashSet};
+use log::{debug, info};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use crate::solitare::solitare::Game;
+use crate::solitare::state::{CardPosition, GameFinalState, GameMove};
+use crate::solitare::view::GameView;
+use crate::solver::autoplay::autoplay;
+use crate::solver::greedy;
+use crate::solver::search::Searcher;
+use crate::solver::transposition::PositionKey;
+use crate::solver::SolveResult;
+
+//Positions searched per sampled deal, unless told otherwise - each move samples several deals, so it's far
+//below a single search's budget
+pub const SAMPLE_NODES: usize = 2_000;
+
+//Plays a Game seeing only what a human would, through a GameView. Each move is chosen by dealing the
+//hidden cards at random several times, solving each of those deals, and playing the first move that wins most of them
+pub struct ClosedSolver {
+    pub samples: usize, //Deals of the hidden cards considered per move
+    pub max_nodes: usize, //Search budget per sampled deal
+    pub max_moves: usize, //Give up on the game after this many moves
+    pub nodes: usize, //Positions searched across every sample so far
+    rng: StdRng,
+}
+
+impl ClosedSolver {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            samples: 5,
+            max_nodes: SAMPLE_NODES,
+            max_moves: 500,
+            nodes: 0,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn solve(&mut self, mut game: Box<Game>) -> SolveResult {
+        let mut moves: Vec<GameMove> = Vec::new();
+        let mut visited: HashSet<PositionKey> = HashSet::new();
+        let mut seen = [false; 52];
+
+        loop {
+            //Safe foundation moves only ever take visible cards
+            autoplay(&mut game, &mut moves);
+
+            match game.get_game_state().get_final_state() {
+                GameFinalState::WON => {
+                    info!("WON in {} moves without looking at hidden cards", moves.len());
+                    return SolveResult::Won(moves)
+                },
+                GameFinalState::LOST => {
+                    info!("LOST after {} moves", moves.len());
+                    return SolveResult::Lost
+                },
+                GameFinalState::UNFINISHED => (),
+            }
+
+            if moves.len() >= self.max_moves {
+                return SolveResult::Stuck(format!("no win after {} moves", moves.len()))
+            }
+
+            //Repeating a position never helps. The hidden cards can't change while hidden, so comparing
+            //whole positions is the same as comparing what's visible. Positions are compared after the safe
+            //foundation moves that follow, which would otherwise put a card taken down straight back up
+            visited.insert(PositionKey::new(&game));
+            let game_move = self.rank_moves(&GameView::remembering(&game, &seen)).into_iter().find(|game_move| {
+                let mut next_game = game.clone();
+                game_move.execute(&mut next_game);
+                autoplay(&mut next_game, &mut Vec::new());
+                !visited.contains(&PositionKey::new(&next_game))
+            });
+
+            match game_move {
+                Some(game_move) => {
+                    debug!(" - Making closed move {}", game_move.debug_move(&game));
+
+                    let waste_len = game.waste.len();
+                    game_move.execute(&mut game);
+
+                    //Remember every card a draw turned up, it keeps its place in the stock/waste cycle
+                    if game_move.from == CardPosition::Stock {
+                        for card in &game.waste[waste_len..] {
+                            seen[card.index()] = true;
+                        }
+                    }
+                    moves.push(game_move);
+                },
+                None => return SolveResult::Stuck(format!("every move repeats an earlier position after {} moves", moves.len())),
+            }
+        }
+    }
+
+    //Best next move for the visible position, if it has any
+    pub fn hint(&mut self, view: &GameView) -> Option<GameMove> {
+        self.rank_moves(view).into_iter().next()
+    }
+
+    //Every move of the visible position, most sampled wins first
+    pub fn rank_moves(&mut self, view: &GameView) -> Vec<GameMove> {
+        let mut votes: HashMap<GameMove, usize> = HashMap::new();
+        let mut positive: HashSet<GameMove> = HashSet::new();
+        let mut all_moves: Vec<GameMove> = Vec::new();
+
+        for _ in 0..self.samples {
+            let sample = Box::new(view.sample(&mut self.rng));
+
+            //Every sample offers the same moves, as moves only ever start from visible cards
+            if all_moves.is_empty() {
+                let game_state = sample.get_game_state();
+                all_moves = game_state.get_all_moves_youch().into_iter().cloned().collect();
+
+                //Flips, foundation moves and the greedy solver's judgement separate moves no sample managed to win with
+                for game_move in &all_moves {
+                    let is_positive = match (&game_move.from, &game_move.to) {
+                        (CardPosition::TableDownturned { .. }, _) | (_, CardPosition::Ace { .. }) => true,
+                        (CardPosition::Stock, _) => false,
+                        _ => greedy::is_move_positive(game_move, &sample, &game_state).0,
+                    };
+                    if is_positive {
+                        positive.insert(game_move.clone());
+                    }
+                }
+            }
+
+            let mut searcher = Searcher::new(self.max_nodes);
+            let result = searcher.search(&sample);
+            self.nodes += searcher.nodes;
+            let result = match result {
+                SolveResult::BudgetExceeded => greedy::solve(sample),
+                result => result,
+            };
+
+            if let SolveResult::Won(sample_moves) = result {
+                if let Some(first_move) = sample_moves.into_iter().next() {
+                    *votes.entry(first_move).or_insert(0) += 1;
+                }
+            }
+        }
+
+        debug!(" - Closed votes {:?}", votes);
+
+        //Most wins first, then positive moves, ties broken by move priority so the order doesn't depend on HashMap order
+        all_moves.sort_by_key(|game_move| (
+            std::cmp::Reverse(votes.get(game_move).copied().unwrap_or(0)),
+            !positive.contains(game_move)
+        ));
+        all_moves
+    }
+}
@@ -0,0 +1,231 @@
+use log::{debug, info, trace};
+use crate::solitare::solitare::Game;
+use crate::solitare::state::{CardPosition, GameFinalState, GameMove, GameState};
+use crate::solver::autoplay::autoplay;
+use crate::solver::pair_cards::is_free_placement;
+use crate::solver::strategy::{GreedyStrategy, Strategy};
+use crate::solver::SolveResult;
+
+pub fn solve(game: Box<Game>) -> SolveResult {
+    play(game, &GreedyStrategy)
+}
+
+//Gives up on a game after this many moves - a strategy that goes round in circles never ends it otherwise
+const MAX_MOVES: usize = 5_000;
+
+//Plays whichever move the strategy ranks first, until the game ends or the strategy has nothing left.
+//Safe foundation moves are made between each step, whatever the strategy
+pub fn play(mut game: Box<Game>, strategy: &dyn Strategy) -> SolveResult {
+    let mut moves: Vec<GameMove> = Vec::new();
+
+    loop {
+        autoplay(&mut game, &mut moves);
+        let game_state = game.get_game_state();
+
+        match game_state.get_final_state() {
+
+            GameFinalState::UNFINISHED => {
+                trace!("game_state: {:?}", game_state);
+
+                if moves.len() >= MAX_MOVES {
+                    return SolveResult::Stuck(format!("no win after {} moves", moves.len()))
+                }
+
+                match strategy.rank(&game, &game_state, &moves).into_iter().next() {
+                    Some(game_move) => {
+                        debug!(" - Making move {}: {}", game_move, explain_move(&game_move, &game));
+
+                        game_move.execute(&mut game);
+                        moves.push(game_move);
+                    },
+                    None => {
+                        //Made no moves - log every potential move and why we wont make it
+                        debug!("Made no moves, potential moves:");
+                        for game_move in game_state.get_all_moves_youch() {
+                            debug!("Move {}\npositivity {:?}\n", game_move.debug_move(&game), is_move_positive(game_move, &game, &game_state))
+                        }
+
+                        //TODO: Complete moves that aren't positive as desperate last attempt
+                        return SolveResult::Stuck(format!("no positive move among {} candidates after {} moves", game_state.get_all_moves_youch().len(), moves.len()))
+                    },
+                }
+            },
+            GameFinalState::LOST => {
+                info!("LOST after {} moves", moves.len());
+
+                return SolveResult::Lost
+            },
+            GameFinalState::WON => {
+                info!("WON in {} moves", moves.len());
+
+                return SolveResult::Won(moves)
+            }
+        }
+    }
+}
+
+pub(crate) fn is_move_positive<'a>(game_move: &GameMove, game: &Game, game_state: &GameState) -> (bool, &'a str) {
+    //A move is positive if:
+    // - It creates space for a queuing king DONE
+    // - It reveals a card beneath DONE
+    // - It enables a positive move //TODO: Create positivity priority in this is only half as positive as others
+    // - It places a card from the deck that the pair card rules let go freely
+
+    //Consider that we may be moving multiple cards in a single move
+    match game_move.from {
+        CardPosition::TableUpturned { stack_index, upturned_index } => {
+            let stack = &game.table[stack_index as usize];
+
+            if upturned_index == 0 {
+                if stack.downturned.is_empty() {
+                    if game_state.queuing_kings > 0 {
+                        //We create space for a king - do the move
+
+                        return (true, "creates space for a queuing king");
+                    } else {
+                        return (false, "no kings are queuing for the space")
+                    }
+                }else{
+                    //We reveal a card beneath - do the move
+
+                    return (true, "reveals a card");
+                }
+            }
+        },
+        CardPosition::TableDownturned { .. } => {
+            //Greedy always flips, but a strategy can leave a flip out and ask why
+            return (true, "reveals a card");
+        },
+        CardPosition::DrawDeck { .. } | CardPosition::Waste => {
+            let card = game.get_draw_card(&game_move.from).expect("Moves from the draw pile always have a card");
+
+            //We can only ever deem a move positive via creating a second move when we're adding an external card to the table
+            //This also guarantees that we're only moving a single card
+
+            if let CardPosition::TableUpturned { stack_index, upturned_index } = game_move.to {
+                let to_stack = &game.table[stack_index as usize];
+
+                //Check we're adding to the bottom of a stack
+                if to_stack.upturned.len() as i8 == upturned_index {
+
+                    //Check every other root upturned card
+                    for table_stack in &game.table {
+                        if let Some(root_card) = table_stack.upturned.first() {
+
+                            if !card.suit().same_color(root_card.suit())
+                                && root_card.number() == card.number() + 1
+                            {
+                                //This move at least enables a further move
+
+                                return (true, "enables a move");
+                            }
+                        }
+                    }
+                    //The pair card rules - the card can still go if it won't take a place its pair card needs
+                    if is_free_placement(game, *card) {
+                        return (true, "its pair card won't need the place")
+                    }
+                    return (false, "doesn't enable a move, and its pair card may need the place")
+                }
+            }
+        },
+        CardPosition::Stock => {
+            //Turning the stock over never directly helps
+        },
+        CardPosition::Ace { suit_index } => {
+            let card = game.aces[suit_index as usize].ace_stack.last().expect("Moves from an ace stack always have a card");
+
+            //Only worth taking a card back down if something then moves onto it, revealing a card beneath
+            for table_stack in &game.table {
+                if let Some(root_card) = table_stack.upturned.first() {
+
+                    if !table_stack.downturned.is_empty() && !card.suit().same_color(root_card.suit()) && root_card.number() == card.number() - 1 {
+                        return (true, "lets a stack move onto it, revealing a card");
+                    }
+                }
+            }
+            return (false, "doesn't enable a revealing move")
+        }
+    }
+
+    (false, "")
+}
+
+//Why the greedy rules would make a move, or why not - in words for a player
+pub fn explain_move(game_move: &GameMove, game: &Game) -> String {
+    let game_state = game.get_game_state();
+
+    //The moves greedy makes unconditionally
+    let reason = match (&game_move.from, &game_move.to) {
+        (CardPosition::TableDownturned { .. }, _) => Some("turns over a downturned card"),
+        (CardPosition::Stock, _) => Some("turns the stock over, as nothing better is on offer"),
+        (_, CardPosition::Stock) => Some("turns the waste back over, to go through the stock again"),
+        _ if game_state.table_ace_moves.contains(game_move) || game_state.draw_ace_moves.contains(game_move) => Some("puts up an ace"),
+        _ if game_state.table_king_moves.contains(game_move) => Some("moves a king into a vacant stack"),
+        _ => None,
+    };
+    if let Some(reason) = reason {
+        return String::from(reason);
+    }
+
+    match is_move_positive(game_move, game, &game_state) {
+        (true, reason) => String::from(reason),
+        (false, "") => String::from("not a positive move by the greedy rules"),
+        (false, reason) => format!("not a positive move by the greedy rules - {}", reason),
+    }
+}
+
+//True once the last moves have turned the whole stock over without playing anything - drawing again would only repeat them
+pub(crate) fn is_stock_exhausted(game: &Game, moves: &[GameMove]) -> bool {
+    let stock_moves_in_a_row = moves.iter().rev()
+        .take_while(|game_move| game_move.from == CardPosition::Stock || game_move.to == CardPosition::Stock)
+        .count();
+    let draw_count = game.rules.draw_count().max(1);
+    let moves_per_pass = (game.draw.len() + game.waste.len()).div_ceil(draw_count) + 1;
+
+    stock_moves_in_a_row > moves_per_pass
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solver::score::Weights;
+    use crate::solver::strategy::ScoredStrategy;
+    use super::*;
+
+    //Weights that score every flip below zero, so the scored strategy never makes one
+    fn flip_rejecting() -> ScoredStrategy {
+        ScoredStrategy { weights: "reveal=-1000,hidden=0,follow_up=0".parse::<Weights>().unwrap() }
+    }
+
+    #[test]
+    fn flips_are_positive() {
+        let game: Game = "
+            foundation H:0 D:0 C:0 S:0
+            stock AH 2H 3H 4H 5H 6H 7H 8H 9H TH JH QH AD 2D 3D 4D 5D 6D 7D 8D 9D TD JD QD
+            waste
+            1: KH QS
+            2: 5c
+            3: KD
+            4: Ac 2c 3c 4c 6c 7C
+            5: 8c 9c Tc Jc Qc KC
+            6: As 2s 3s 4s 5s 6S
+            7: 7s 8s 9s Ts Js KS
+        ".parse().unwrap();
+        let game_state = game.get_game_state();
+        let flip = "flip d2.1".parse::<GameMove>().unwrap();
+        assert!(game_state.table_flip_moves.contains(&flip));
+
+        assert_eq!(is_move_positive(&flip, &game, &game_state), (true, "reveals a card"));
+        assert_eq!(explain_move(&flip, &game), "turns over a downturned card");
+    }
+
+    #[test]
+    fn play_ends_when_the_strategy_rejects_every_flip() {
+        for seed in 0..8 {
+            match play(Box::new(Game::from_seed(seed)), &flip_rejecting()) {
+                SolveResult::Won(_) | SolveResult::Lost | SolveResult::Stuck(_) => (),
+                SolveResult::BudgetExceeded => panic!("greedy play has no budget"),
+            }
+        }
+    }
+}
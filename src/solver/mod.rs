@@ -0,0 +1,106 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use crate::solitare::solitare::Game;
+use crate::solitare::state::GameMove;
+use crate::solver::closed::ClosedSolver;
+use crate::solver::parallel::ParallelSearcher;
+use crate::solver::search::Searcher;
+use crate::solver::strategy::StrategyKind;
+
+pub mod autoplay;
+pub mod batch;
+pub mod closed;
+pub mod greedy;
+pub mod pair_cards;
+pub mod parallel;
+pub mod pool;
+pub mod prune;
+pub mod score;
+pub mod search;
+pub mod strategy;
+pub mod transposition;
+pub mod verify;
+
+#[derive(Debug)]
+pub enum SolveResult {
+    Won(Vec<GameMove>), //The moves played from the starting position
+    Lost, //No moves remain (or, for search, none ever lead to a win)
+    Stuck(String), //The solver stopped with moves still available - inconclusive, with the reason
+    BudgetExceeded, //Search ran out of nodes before deciding
+}
+
+//Which solver to play a game with
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SolverKind {
+    Greedy, //One pass of the greedy heuristic
+    Search, //Exhaustive depth first search, seeing every card
+    Parallel, //The same search split across threads - the budget applies to each subtree
+    Closed, //Sampling search that only sees what a player would
+}
+
+impl SolverKind {
+    //The budget max_nodes means when none is given - per sampled deal for the closed solver, which searches many
+    pub fn default_max_nodes(self) -> usize {
+        match self {
+            SolverKind::Closed => closed::SAMPLE_NODES,
+            SolverKind::Greedy | SolverKind::Search | SolverKind::Parallel => 500_000,
+        }
+    }
+
+    pub fn solve(self, game: &Game, max_nodes: usize) -> SolveResult {
+        self.solve_counting(game, max_nodes).0
+    }
+
+    //Also returns the number of positions searched - always 0 for greedy, which never looks ahead
+    pub fn solve_counting(self, game: &Game, max_nodes: usize) -> (SolveResult, usize) {
+        self.solve_counting_with(game, max_nodes, StrategyKind::default(), pool::default_threads())
+    }
+
+    //The strategy picks the greedy solver's moves, and a scored one orders the moves search tries.
+    //threads is only used by the parallel solver
+    pub fn solve_counting_with(self, game: &Game, max_nodes: usize, strategy: StrategyKind, threads: usize) -> (SolveResult, usize) {
+        match self {
+            SolverKind::Greedy => (greedy::play(Box::new(*game), strategy.strategy().as_ref()), 0),
+            SolverKind::Search => {
+                let mut searcher = Searcher::new(max_nodes);
+                searcher.weights = strategy.weights();
+                (searcher.search(game), searcher.nodes)
+            },
+            SolverKind::Parallel => {
+                let mut parallel_searcher = ParallelSearcher::new(max_nodes, threads);
+                parallel_searcher.weights = strategy.weights();
+                (parallel_searcher.search(game), parallel_searcher.nodes)
+            },
+            SolverKind::Closed => {
+                let mut closed_solver = ClosedSolver::new(0);
+                closed_solver.max_nodes = max_nodes;
+                (closed_solver.solve(Box::new(*game)), closed_solver.nodes)
+            },
+        }
+    }
+}
+
+impl Display for SolverKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolverKind::Greedy => write!(f, "greedy"),
+            SolverKind::Search => write!(f, "search"),
+            SolverKind::Parallel => write!(f, "parallel"),
+            SolverKind::Closed => write!(f, "closed"),
+        }
+    }
+}
+
+impl FromStr for SolverKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "greedy" => Ok(SolverKind::Greedy),
+            "search" => Ok(SolverKind::Search),
+            "parallel" => Ok(SolverKind::Parallel),
+            "closed" => Ok(SolverKind::Closed),
+            _ => Err(format!("unknown solver '{}', expected greedy, search, parallel or closed", s)),
+        }
+    }
+}
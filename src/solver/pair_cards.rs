@@ -0,0 +1,237 @@
+use crate::solitare::card::Card;
+use crate::solitare::solitare::Game;
+use crate::solitare::state::{CardPosition, GameMove};
+
+//The pair card rules of optimal.txt. A card's pair card is the other card of the same number and colour -
+//5H and 5D, or JC and JS. Both go on the same two cards on the table, so each placement of one can take a place
+//the other will want. A card can be placed freely, without worrying about its pair, when:
+// - its pair card is on the aces, so never needs a place
+// - its pair card is upturned - already on the table, so free to take the other place whenever it moves
+// - it and its pair card are both in the deck, where either can wait for a place while the other takes one
+//Otherwise its pair card is downturned, and will want one of those places once it's turned over
+
+pub fn pair_card(card: Card) -> Card {
+    //Hearts and diamonds, clubs and spades, are suit indices 0 and 1, 2 and 3
+    let pair_suit_index = card.suit_index() ^ 1;
+    Card::new(pair_suit_index * 13 + card.number() - 1)
+}
+
+//Where a card is, as far as the pair card rules care
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Whereabouts {
+    Aces,
+    Upturned,
+    Downturned,
+    Deck, //The stock or waste
+}
+
+pub fn whereabouts(game: &Game, card: Card) -> Whereabouts {
+    if game.aces.iter().any(|ace_stack| ace_stack.ace_stack.contains(&card)) {
+        return Whereabouts::Aces;
+    }
+
+    for table_stack in &game.table {
+        if table_stack.downturned.contains(&card) {
+            return Whereabouts::Downturned;
+        }
+        if table_stack.upturned.contains(&card) {
+            return Whereabouts::Upturned;
+        }
+    }
+
+    Whereabouts::Deck
+}
+
+//Whether the card can take a place on the table without taking it from its pair card
+pub fn is_free_placement(game: &Game, card: Card) -> bool {
+    match whereabouts(game, pair_card(card)) {
+        Whereabouts::Aces | Whereabouts::Upturned => true,
+        Whereabouts::Deck => whereabouts(game, card) == Whereabouts::Deck,
+        Whereabouts::Downturned => false,
+    }
+}
+
+//Orders moves onto the table by the pair card rules, most free first:
+// - of two cards that could take the same place, the one that can be placed freely goes first, leaving the place
+//   to the pair card that needs it
+// - of two equal places for the same card - a pair of cards, both upturned - either will do, so the one on a stack
+//   with the fewest downturned cards goes first, as the run built there is the least likely to have to move again
+//Stable, so moves the rules don't separate keep their order
+pub fn sort_by_pair_rules(game: &Game, game_moves: &mut [GameMove]) {
+    game_moves.sort_by_key(|game_move| {
+        let card = moving_card(game, &game_move.from);
+        let free = card.is_none_or(|card| is_free_placement(game, card));
+        let destination_downturned = match game_move.to {
+            CardPosition::TableUpturned { stack_index, .. } => game.table[stack_index as usize].downturned.len(),
+            _ => 0,
+        };
+
+        (!free, destination_downturned)
+    });
+}
+
+//The card a move puts onto the destination - the bottom card of the run, for moves within the table
+fn moving_card(game: &Game, from: &CardPosition) -> Option<Card> {
+    match *from {
+        CardPosition::TableUpturned { stack_index, upturned_index } => game.table[stack_index as usize].upturned.get(upturned_index as usize).copied(),
+        CardPosition::Ace { suit_index } => game.aces[suit_index as usize].ace_stack.last().copied(),
+        CardPosition::DrawDeck { .. } | CardPosition::Waste => game.get_draw_card(from).copied(),
+        CardPosition::TableDownturned { .. } | CardPosition::Stock => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solver::score::Weights;
+    use crate::solver::strategy::{GreedyStrategy, ScoredStrategy, Strategy};
+    use super::*;
+
+    //Hearts up to 5H on the aces, and each way a pair card can be placed somewhere on the table or in the stock
+    const LAYOUT: &str = "
+        draw three
+        redeals unlimited
+        recycled 0
+        foundation H:5 D:0 C:0 S:0
+        stock 3C 3S 8D 5D 6H 7H 9H TH JH QH KH AD 2D 3D 6D 9D JD QD KD AC 4C 5C 6C 7C
+        waste
+        1: 4d Jc TD 9S
+        2: KC
+        3: 2c 7D
+        4: 8c 8H
+        5: 9c Tc Qc As 2S
+        6: 4s 5s 6s 7s 8S
+        7: Ts Js Qs KS
+    ";
+
+    fn card(token: &str) -> Card {
+        token.parse().unwrap()
+    }
+
+    #[test]
+    fn pair_cards_share_number_and_colour() {
+        assert_eq!(pair_card(card("5H")), card("5D"));
+        assert_eq!(pair_card(card("5D")), card("5H"));
+        assert_eq!(pair_card(card("JC")), card("JS"));
+        assert_eq!(pair_card(card("KS")), card("KC"));
+    }
+
+    #[test]
+    fn whereabouts_of_each_place() {
+        let game: Game = LAYOUT.parse().unwrap();
+
+        assert_eq!(whereabouts(&game, card("5H")), Whereabouts::Aces);
+        assert_eq!(whereabouts(&game, card("9S")), Whereabouts::Upturned);
+        assert_eq!(whereabouts(&game, card("KC")), Whereabouts::Upturned);
+        assert_eq!(whereabouts(&game, card("7D")), Whereabouts::Upturned);
+        assert_eq!(whereabouts(&game, card("JC")), Whereabouts::Downturned);
+        assert_eq!(whereabouts(&game, card("8D")), Whereabouts::Deck);
+    }
+
+    #[test]
+    fn free_when_the_pair_card_never_needs_the_place() {
+        let game: Game = LAYOUT.parse().unwrap();
+
+        assert!(is_free_placement(&game, card("5D")), "5H is on the aces");
+        assert!(is_free_placement(&game, card("9C")), "9S is upturned on TD");
+        assert!(is_free_placement(&game, card("KS")), "KC is upturned at the root of a stack with nothing beneath");
+        assert!(is_free_placement(&game, card("7H")), "7D is upturned, though still covering a downturned card");
+        assert!(is_free_placement(&game, card("8D")), "8H is upturned, though still covering a downturned card");
+        assert!(is_free_placement(&game, card("3C")), "3C and 3S are both in the stock");
+    }
+
+    #[test]
+    fn blocked_when_the_pair_card_may_need_the_place() {
+        let game: Game = LAYOUT.parse().unwrap();
+
+        assert!(!is_free_placement(&game, card("JS")), "JC is downturned");
+        assert!(!is_free_placement(&game, card("TS")), "TC is downturned");
+        assert!(!is_free_placement(&game, card("8H")), "8D is in the stock, and 8H isn't");
+    }
+
+    fn game_move(notation: &str) -> GameMove {
+        notation.parse().unwrap()
+    }
+
+    //The moves each strategy would make from the stock, best first
+    fn ranked_deck_moves(game: &Game) -> Vec<(&'static str, Vec<GameMove>)> {
+        let game_state = game.get_game_state();
+        let strategies: [(&str, Box<dyn Strategy>); 2] = [
+            ("greedy", Box::new(GreedyStrategy)),
+            ("scored", Box::new(ScoredStrategy { weights: Weights::default() })),
+        ];
+
+        strategies.into_iter()
+            .map(|(name, strategy)| {
+                let ranked = strategy.rank(game, &game_state, &[]);
+                (name, ranked.into_iter().filter(|game_move| game_state.deck_moves.contains(game_move)).collect())
+            })
+            .collect()
+    }
+
+    //8H on the waste, free as 8D is on the aces, can go on 9C with three downturned cards beneath or 9S with one
+    const TWO_PLACES: &str = "
+        foundation H:0 D:8 C:0 S:0
+        stock AH 2H 3H 4H 5H 6H 7H 9H JH QH KH 9D JD KD AC 8C TC JC QC KC AS JS QS
+        waste 8H
+        1: 2c 3c 4c TD 9C
+        2: 5c TH 9S
+        3: KS
+        4: 6c 7c QD
+        5: 2s 3s 4s 5s 6s 7S
+        6: 8S
+        7: TS
+    ";
+
+    //Open draw, with 8D and 5S both playable from the stock. 8H is downturned, so 8D may take its place on 9C -
+    //5C is upturned, so 5S can go on 6H freely
+    const TWO_CARDS: &str = "
+        draw open
+        foundation H:0 D:0 C:0 S:0
+        stock 3H 4H 5H 7H 9H TH JH QH KH AD 3D 4D 5D 6D 7D 8D 9D JD QD 3C 7C AS 5S
+        waste
+        1: TD 9C
+        2: 7S 6H
+        3: KD
+        4: 8h 2d 5C
+        5: 2s 3s 4s 6s 8s 9s TS
+        6: Js Qs Ks Ac 2c 6c 4C
+        7: 8c Tc Jc Qc Kc ah 2H
+    ";
+
+    #[test]
+    fn sort_prefers_the_place_with_fewest_downturned_beneath() {
+        let game: Game = TWO_PLACES.parse().unwrap();
+        let mut game_moves = vec![game_move("w-t1.3"), game_move("w-t2.3")];
+
+        sort_by_pair_rules(&game, &mut game_moves);
+        assert_eq!(game_moves, vec![game_move("w-t2.3"), game_move("w-t1.3")]);
+    }
+
+    #[test]
+    fn sort_prefers_the_card_placed_freely() {
+        let game: Game = TWO_CARDS.parse().unwrap();
+        let mut game_moves = vec![game_move("s16-t1.3"), game_move("s23-t2.3")];
+
+        sort_by_pair_rules(&game, &mut game_moves);
+        assert_eq!(game_moves, vec![game_move("s23-t2.3"), game_move("s16-t1.3")]);
+    }
+
+    #[test]
+    fn strategies_choose_the_place_with_fewest_downturned_beneath() {
+        let game: Game = TWO_PLACES.parse().unwrap();
+
+        for (name, deck_moves) in ranked_deck_moves(&game) {
+            assert_eq!(deck_moves, vec![game_move("w-t2.3"), game_move("w-t1.3")], "{} strategy", name);
+        }
+    }
+
+    #[test]
+    fn strategies_choose_the_card_placed_freely() {
+        let game: Game = TWO_CARDS.parse().unwrap();
+
+        for (name, deck_moves) in ranked_deck_moves(&game) {
+            assert!(deck_moves.contains(&game_move("s23-t2.3")), "{} strategy leaves out 5S onto 6H", name);
+            assert!(!deck_moves.contains(&game_move("s16-t1.3")), "{} strategy makes 8D onto 9C", name);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use log::debug;
+use crate::solitare::apply::apply;
+use crate::solitare::solitare::Game;
+use crate::solitare::state::GameMove;
+use crate::solver::autoplay::safe_foundation_move;
+use crate::solver::prune::prune_dominated;
+use crate::solver::pool;
+use crate::solver::score::Weights;
+use crate::solver::search::Searcher;
+use crate::solver::transposition::{PositionKey, TranspositionTable};
+use crate::solver::SolveResult;
+
+const SPLIT_SUBTREES: usize = 16; //Split the first few moves out until there are at least this many subtrees
+const MAX_SPLIT_DEPTH: usize = 3;
+const WAVE_SUBTREES: usize = 8; //Subtrees searched between each sharing of dead positions
+
+//Searches one deal across threads. The first moves are split out into subtrees, each searched depth first by its own Searcher.
+//Subtrees are searched in fixed waves - every position of a subtree that comes back Lost is proven dead and shared with the
+//following waves, so they never search it again. Waves don't depend on the thread count, so neither does the result
+pub struct ParallelSearcher {
+    pub max_nodes: usize, //Search budget of each subtree
+    pub threads: usize,
+    pub weights: Option<Weights>, //Move order within each subtree, as for Searcher
+    pub nodes: usize, //Positions searched across every subtree
+}
+
+struct Subtree {
+    path: Vec<GameMove>, //Moves from the starting position
+    game: Game,
+}
+
+impl ParallelSearcher {
+    pub fn new(max_nodes: usize, threads: usize) -> Self {
+        Self {
+            max_nodes,
+            threads,
+            weights: None,
+            nodes: 0,
+        }
+    }
+
+    pub fn search(&mut self, game: &Game) -> SolveResult {
+        self.nodes = 0;
+
+        let subtrees = match split(game) {
+            Ok(subtrees) => subtrees,
+            Err(result) => return result,
+        };
+        debug!("Split search into {} subtrees", subtrees.len());
+
+        let mut dead: Arc<HashSet<PositionKey>> = Arc::new(HashSet::new());
+        let mut budget_exceeded = false;
+
+        for wave in subtrees.chunks(WAVE_SUBTREES) {
+            let results = pool::map(wave, self.threads, |subtree| {
+                let mut searcher = Searcher::new(self.max_nodes);
+                searcher.table = TranspositionTable::with_dead(Arc::clone(&dead));
+                searcher.weights = self.weights;
+                let result = searcher.search(&subtree.game);
+                (result, searcher.nodes, searcher.table.take_positions())
+            });
+
+            //Taken in subtree order, so the first win in move priority order is the one returned
+            for (subtree, (result, nodes, positions)) in wave.iter().zip(results) {
+                self.nodes += nodes;
+
+                match result {
+                    SolveResult::Won(moves) => {
+                        let mut path = subtree.path.clone();
+                        path.extend(moves);
+                        return SolveResult::Won(path);
+                    },
+                    SolveResult::Lost => Arc::make_mut(&mut dead).extend(positions),
+                    SolveResult::BudgetExceeded => budget_exceeded = true,
+                    SolveResult::Stuck(_) => unreachable!("Search is never stuck"),
+                }
+            }
+            debug!("Wave searched, {} positions proven dead", dead.len());
+        }
+
+        //Lost is only a proof if every subtree was searched to the end
+        if budget_exceeded { SolveResult::BudgetExceeded } else { SolveResult::Lost }
+    }
+}
+
+//Plays out every line of the first few moves breadth first. Positions reached by more than one line are kept once.
+//Returns the decided result instead if the game is won or lost within the split
+fn split(game: &Game) -> Result<Vec<Subtree>, SolveResult> {
+    let mut seen: HashSet<PositionKey> = HashSet::new();
+    seen.insert(PositionKey::new(game));
+
+    let mut subtrees = vec![Subtree { path: Vec::new(), game: *game }];
+
+    for _ in 0..MAX_SPLIT_DEPTH {
+        if subtrees.len() >= SPLIT_SUBTREES {
+            break
+        }
+
+        let mut next_subtrees = Vec::new();
+        for subtree in &subtrees {
+            if subtree.game.aces.iter().all(|ace_stack| ace_stack.is_full()) {
+                return Err(SolveResult::Won(subtree.path.clone()));
+            }
+
+            //Forced the same as in search, so a safe foundation move never splits
+            let game_moves: Vec<GameMove> = match safe_foundation_move(&subtree.game) {
+                Some(safe_move) => vec![safe_move],
+                None => {
+                    let mut game_moves = subtree.game.get_game_state().get_all_moves_youch().into_iter().cloned().collect();
+                    prune_dominated(&subtree.game, &mut game_moves);
+                    game_moves
+                },
+            };
+
+            for game_move in game_moves {
+                let mut next_game = subtree.game;
+                apply(&mut next_game, &game_move).expect("Generated moves are always legal");
+
+                if seen.insert(PositionKey::new(&next_game)) {
+                    let mut path = subtree.path.clone();
+                    path.push(game_move);
+                    next_subtrees.push(Subtree { path, game: next_game });
+                }
+            }
+        }
+
+        //Every line ran out of moves without winning
+        if next_subtrees.is_empty() {
+            return Err(SolveResult::Lost);
+        }
+        subtrees = next_subtrees;
+    }
+
+    Ok(subtrees)
+}
@@ -0,0 +1,34 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+//Runs job over every item on a pool of threads, each taking the next item as it finishes the last
+//Results come back in item order, so they never depend on the number of threads or how the work was shared out
+pub fn map<T, R, F>(items: &[T], threads: usize, job: F) -> Vec<R>
+    where T: Sync, R: Send, F: Fn(&T) -> R + Sync
+{
+    let next_item = AtomicUsize::new(0);
+
+    let mut results: Vec<(usize, R)> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.max(1).min(items.len())).map(|_| {
+            scope.spawn(|| {
+                let mut results = Vec::new();
+                loop {
+                    let item_index = next_item.fetch_add(1, Ordering::Relaxed);
+                    match items.get(item_index) {
+                        Some(item) => results.push((item_index, job(item))),
+                        None => return results,
+                    }
+                }
+            })
+        }).collect();
+
+        workers.into_iter().flat_map(|worker| worker.join().expect("Worker thread panicked")).collect()
+    });
+    results.sort_by_key(|(item_index, _)| *item_index);
+
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+//Threads to use when none are asked for
+pub fn default_threads() -> usize {
+    std::thread::available_parallelism().map(|threads| threads.get()).unwrap_or(1)
+}
@@ -0,0 +1,157 @@
+use crate::solitare::card::Card;
+use crate::solitare::solitare::Game;
+use crate::solitare::state::{CardPosition, GameMove};
+use crate::solver::pair_cards::pair_card;
+
+//Removes the moves search can leave out, as some other line - or the same move made later - always does at least as well:
+// - A whole stack with nothing beneath it moving into a vacant stack. The position is the same with the stacks
+//   numbered differently. A king moving between vacant stacks is the usual case - get_table_king_moves already
+//   leaves these out, the rule catches any added from elsewhere
+// - Part of a run moving off its card onto that card's pair - the only other card it fits on, as runs alternate
+//   colour and descend. The table only changes beneath the run, so the move is only worth making once the card it
+//   uncovers has somewhere to go, up to its ace stack, or something to take: the pair card of the run's first card,
+//   the only other card that fits on it. Until then the run can stay where it is, and make the same move later.
+//   This covers moving a run back and forth between two equal cards
+//Moves straight back to the previous position aren't pruned here - the transposition table already knows the position
+pub fn prune_dominated(game: &Game, game_moves: &mut Vec<GameMove>) {
+    game_moves.retain(|game_move| !is_dominated(game, game_move));
+}
+
+pub fn is_dominated(game: &Game, game_move: &GameMove) -> bool {
+    let (CardPosition::TableUpturned { stack_index, upturned_index }, CardPosition::TableUpturned { stack_index: to_stack, .. }) = (&game_move.from, &game_move.to) else {
+        return false;
+    };
+    let table_stack = &game.table[*stack_index as usize];
+    let upturned_index = *upturned_index as usize;
+
+    if upturned_index == 0 {
+        let to_stack = &game.table[*to_stack as usize];
+        return table_stack.downturned.is_empty() && to_stack.downturned.is_empty() && to_stack.upturned.is_empty();
+    }
+
+    let uncovered = table_stack.upturned[upturned_index - 1];
+    let can_go_up = game.aces[uncovered.suit_index() as usize].ace_stack.len() as i8 == uncovered.number() - 1;
+
+    !can_go_up && !is_available(game, pair_card(table_stack.upturned[upturned_index]))
+}
+
+//Whether the card could move now, given somewhere to go - upturned on the table, playable from the stock, or on top of its ace stack
+fn is_available(game: &Game, card: Card) -> bool {
+    game.table.iter().any(|table_stack| table_stack.upturned.contains(&card))
+        || game.get_playable_draw_cards().iter().any(|(draw_card, _)| **draw_card == card)
+        || game.aces[card.suit_index() as usize].ace_stack.last() == Some(&card)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solitare::apply::apply;
+    use super::*;
+
+    //The moves of the position before and after pruning
+    fn moves_and_pruned(layout: &str) -> (Vec<GameMove>, Vec<GameMove>) {
+        let game: Game = layout.parse().unwrap();
+        let game_moves: Vec<GameMove> = game.get_game_state().get_all_moves_youch().into_iter().cloned().collect();
+        let mut pruned = game_moves.clone();
+        prune_dominated(&game, &mut pruned);
+
+        (game_moves, pruned)
+    }
+
+    fn game_move(notation: &str) -> GameMove {
+        notation.parse().unwrap()
+    }
+
+    //KH QS has nothing beneath it, KD has a downturned card
+    const KINGS: &str = "
+        foundation H:0 D:0 C:0 S:0
+        stock AH 2H 3H 4H 5H 6H 7H 8H 9H TH JH QH AD 2D 3D 4D 5D 6D 7D 8D 9D TD JD QD
+        waste
+        1: KH QS
+        2:
+        3: 5c KD
+        4: Ac 2c 3c 4c 6c 7C
+        5: 8c 9c Tc Jc Qc KC
+        6: As 2s 3s 4s 5s 6S
+        7: 7s 8s 9s Ts Js KS
+    ";
+
+    #[test]
+    fn whole_stack_into_a_vacant_stack_is_pruned() {
+        let game: Game = KINGS.parse().unwrap();
+        let mut game_moves = vec![game_move("t1.1-t2.1"), game_move("t3.1-t2.1")];
+        let mut next_game = game;
+        assert!(apply(&mut next_game, &game_moves[0]).is_ok());
+
+        prune_dominated(&game, &mut game_moves);
+        assert_eq!(game_moves, vec![game_move("t3.1-t2.1")]);
+    }
+
+    #[test]
+    fn king_revealing_a_card_into_a_vacant_stack_is_kept() {
+        let (_, pruned) = moves_and_pruned(KINGS);
+
+        assert!(pruned.contains(&game_move("t3.1-t2.1")));
+        assert!(pruned.contains(&game_move("t5.1-t2.1")));
+    }
+
+    //9S 8H can move off TD onto TH. 9C, the only other card that fits on TD, is downturned
+    const PARTIAL_RUN: &str = "
+        foundation H:0 D:0 C:0 S:0
+        stock AH 2H 3H 4H 5H 6H 7H 9H JH QH KH AD 2D 3D 4D 5D 6D 7D 8D 9D JD QD KD AC
+        waste
+        1: 3c TD 9S 8H
+        2: 4c TH
+        3: 9c JS
+        4: 2c 5c 6c 7c 8C
+        5: Tc Jc Qc Kc AS
+        6: 2s 3s 4s 5s 6S
+        7: 7s 8s Ts Qs KS
+    ";
+
+    #[test]
+    fn partial_run_uncovering_nothing_useful_is_pruned() {
+        let (game_moves, pruned) = moves_and_pruned(PARTIAL_RUN);
+
+        assert!(game_moves.contains(&game_move("t1.2-t2.2")));
+        assert!(!pruned.contains(&game_move("t1.2-t2.2")));
+        assert_eq!(pruned.len(), game_moves.len() - 1);
+    }
+
+    #[test]
+    fn partial_run_making_room_for_its_pair_card_is_kept() {
+        //9C upturned, so it can follow onto TD
+        let (_, pruned) = moves_and_pruned("
+            foundation H:0 D:0 C:0 S:0
+            stock AH 2H 3H 4H 5H 6H 7H 9H JH QH KH AD 2D 3D 4D 5D 6D 7D 8D 9D JD QD KD AC
+            waste
+            1: 3c TD 9S 8H
+            2: 4c TH
+            3: 5c 9C
+            4: 2c 6c 7c 8c TC
+            5: Jc Qc Kc As 2S
+            6: 3s 4s 5s 6s 7S
+            7: 8s Ts Js Qs KS
+        ");
+
+        assert!(pruned.contains(&game_move("t1.2-t2.2")));
+    }
+
+    #[test]
+    fn partial_run_uncovering_a_card_to_put_up_is_kept() {
+        //Diamonds up to 9D on the aces, so TD can go up once uncovered
+        let (_, pruned) = moves_and_pruned("
+            foundation H:0 D:9 C:0 S:0
+            stock AH 2H 3H 4H 5H 6H 7H 9H JH QH KH JD QD KD AC 2C 5C 6C 7C 8C TC JC QC KC
+            waste
+            1: 3c TD 9S 8H
+            2: 4c TH
+            3: 9c JS
+            4: As 2s 3S
+            5: 4s 5s 6S
+            6: 7s 8s TS
+            7: Qs KS
+        ");
+
+        assert!(pruned.contains(&game_move("t1.2-t2.2")));
+    }
+}
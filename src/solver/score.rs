@@ -0,0 +1,169 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use crate::solitare::apply::apply;
+use crate::solitare::card::Card;
+use crate::solitare::solitare::Game;
+use crate::solitare::state::{CardPosition, GameMove, GameState};
+use crate::solver::autoplay::is_safe_to_put_up;
+use crate::solver::pair_cards::{is_free_placement, sort_by_pair_rules};
+
+//How much each thing a move does counts towards its score - a graded is_move_positive. Scores are whole
+//numbers so equal moves tie exactly, and fall back on the pair card rules' order
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Weights {
+    pub reveal: i32, //Turns over a downturned card, or moves the cards covering one
+    pub hidden: i32, //Per downturned card still beneath the one revealed, so the deepest stacks are dug into first
+    pub king_space: i32, //Empties a stack while a king is waiting for one
+    pub follow_up: i32, //Per productive move - a reveal or a card put up - the move makes available
+    pub safe_foundation: i32, //Puts up a card nothing left on the table could need to go onto
+    pub foundation: i32, //Puts up any other card
+    pub foundation_down: i32, //Takes a card back down from the aces
+    pub from_stock: i32, //Plays a card out of the stock, onto the table or the aces
+    pub pair_blocked: i32, //Places a stock card where its pair card may need the place
+    pub stock: i32, //Turns the stock or the waste over
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Self {
+            reveal: 100,
+            hidden: 10,
+            king_space: 60,
+            follow_up: 30,
+            safe_foundation: 80,
+            foundation: 20,
+            foundation_down: -40,
+            from_stock: 20,
+            pair_blocked: -30,
+            stock: 1,
+        }
+    }
+}
+
+impl Weights {
+    //Every move of the position with its score, best first
+    pub fn rank(&self, game: &Game, game_state: &GameState) -> Vec<(GameMove, i32)> {
+        let productive_before = productive_moves(game, game_state);
+
+        let mut game_moves: Vec<GameMove> = game_state.get_all_moves_youch().into_iter().cloned().collect();
+        sort_by_pair_rules(game, &mut game_moves);
+
+        let mut scored: Vec<(GameMove, i32)> = game_moves.into_iter()
+            .map(|game_move| {
+                let score = self.score(game, game_state, &game_move, productive_before);
+                (game_move, score)
+            })
+            .collect();
+        scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+
+        scored
+    }
+
+    fn score(&self, game: &Game, game_state: &GameState, game_move: &GameMove, productive_before: usize) -> i32 {
+        let mut score = 0;
+
+        match game_move.from {
+            CardPosition::TableDownturned { stack_index, .. } => {
+                let hidden = game.table[stack_index as usize].downturned.len() - 1;
+                score += self.reveal + self.hidden * hidden as i32;
+            },
+            CardPosition::TableUpturned { stack_index, upturned_index: 0 } => {
+                let table_stack = &game.table[stack_index as usize];
+                match table_stack.downturned.len() {
+                    //A king moving between vacant stacks doesn't make any space
+                    0 => if game_state.queuing_kings > 0 && table_stack.upturned[0].number() != 13 {
+                        score += self.king_space;
+                    },
+                    downturned => score += self.reveal + self.hidden * (downturned - 1) as i32,
+                }
+            },
+            CardPosition::Ace { .. } => score += self.foundation_down,
+            CardPosition::Stock => score += self.stock,
+            CardPosition::DrawDeck { .. } | CardPosition::Waste => match game_move.to {
+                CardPosition::Stock => score += self.stock,
+                CardPosition::TableUpturned { .. } => {
+                    score += self.from_stock;
+                    let card = *game.get_draw_card(&game_move.from).expect("Moves from the draw pile always have a card");
+                    if !is_free_placement(game, card) {
+                        score += self.pair_blocked;
+                    }
+                },
+                _ => score += self.from_stock,
+            },
+            CardPosition::TableUpturned { .. } => (),
+        }
+
+        if let CardPosition::Ace { .. } = game_move.to {
+            let card = moving_card(game, &game_move.from);
+            score += if is_safe_to_put_up(game, card) { self.safe_foundation } else { self.foundation };
+        }
+
+        //Only the productive moves the move adds count - moving cards back and forth never scores both ways
+        let mut next_game = *game;
+        apply(&mut next_game, game_move).expect("Generated moves are always legal");
+        let productive_after = productive_moves(&next_game, &next_game.get_game_state());
+        score += self.follow_up * productive_after.saturating_sub(productive_before) as i32;
+
+        score
+    }
+}
+
+//Reveals and cards put up on offer in the position
+fn productive_moves(game: &Game, game_state: &GameState) -> usize {
+    game_state.get_all_moves_youch().into_iter()
+        .filter(|game_move| match (&game_move.from, &game_move.to) {
+            (CardPosition::TableDownturned { .. }, _) | (_, CardPosition::Ace { .. }) => true,
+            (CardPosition::TableUpturned { stack_index, upturned_index: 0 }, _) => !game.table[*stack_index as usize].downturned.is_empty(),
+            _ => false,
+        })
+        .count()
+}
+
+fn moving_card(game: &Game, from: &CardPosition) -> Card {
+    match *from {
+        CardPosition::TableUpturned { stack_index, .. } => *game.table[stack_index as usize].upturned.last().expect("Moves to the aces take the last card"),
+        CardPosition::DrawDeck { .. } | CardPosition::Waste => *game.get_draw_card(from).expect("Moves from the draw pile always have a card"),
+        _ => unreachable!("Only table and draw pile cards go up to the aces"),
+    }
+}
+
+//Written and read as name=value pairs, like reveal=100,hidden=10 - any left out keep their default
+impl Display for Weights {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "reveal={},hidden={},king_space={},follow_up={},safe_foundation={},foundation={},foundation_down={},from_stock={},pair_blocked={},stock={}",
+            self.reveal, self.hidden, self.king_space, self.follow_up, self.safe_foundation, self.foundation, self.foundation_down, self.from_stock, self.pair_blocked, self.stock
+        )
+    }
+}
+
+impl FromStr for Weights {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut weights = Weights::default();
+
+        for pair in s.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (name, value) = pair.split_once('=').ok_or_else(|| format!("bad weight '{}', expected name=value", pair))?;
+            let value: i32 = value.trim().parse().map_err(|_| format!("weight {} has bad value '{}'", name, value))?;
+
+            let weight = match name.trim() {
+                "reveal" => &mut weights.reveal,
+                "hidden" => &mut weights.hidden,
+                "king_space" => &mut weights.king_space,
+                "follow_up" => &mut weights.follow_up,
+                "safe_foundation" => &mut weights.safe_foundation,
+                "foundation" => &mut weights.foundation,
+                "foundation_down" => &mut weights.foundation_down,
+                "from_stock" => &mut weights.from_stock,
+                "pair_blocked" => &mut weights.pair_blocked,
+                "stock" => &mut weights.stock,
+                name => return Err(format!("unknown weight '{}'", name)),
+            };
+            *weight = value;
+        }
+
+        Ok(weights)
+    }
+}
@@ -0,0 +1,136 @@
+use log::debug;
+use crate::solitare::apply::{apply, AppliedMove};
+use crate::solitare::solitare::Game;
+use crate::solitare::state::GameMove;
+use crate::solver::autoplay::safe_foundation_move;
+use crate::solver::prune::prune_dominated;
+use crate::solver::score::Weights;
+use crate::solver::SolveResult;
+use crate::solver::transposition::TranspositionTable;
+
+pub struct Searcher {
+    pub max_nodes: usize,
+    pub nodes: usize,
+    pub table: TranspositionTable,
+    pub weights: Option<Weights>, //Tries moves best scored first, rather than in generation order
+
+    path: Vec<GameMove>,
+}
+
+impl Searcher {
+    pub fn new(max_nodes: usize) -> Self {
+        Self {
+            max_nodes,
+            nodes: 0,
+            table: TranspositionTable::new(),
+            weights: None,
+            path: Vec::new(),
+        }
+    }
+
+    //Lost here is a proof - every reachable position was explored without finding a win
+    pub fn search(&mut self, game: &Game) -> SolveResult {
+        self.nodes = 0;
+        self.table.clear();
+        self.path.clear();
+
+        let mut game = *game;
+        let result = match self.search_node(&mut game) {
+            Some(true) => SolveResult::Won(std::mem::take(&mut self.path)),
+            Some(false) => SolveResult::Lost,
+            None => SolveResult::BudgetExceeded,
+        };
+
+        let table_stats = self.table.stats();
+        debug!("Searched {} positions, transposition table {} entries with {:.1}% hit rate", self.nodes, table_stats.entries, table_stats.hit_rate() * 100.0);
+
+        result
+    }
+
+    //Depth first over every move of the GameState, in the same priority order the greedy solver uses - or by score,
+    //with safe foundation moves forced.
+    //Iterative rather than recursive as winning lines can be thousands of moves deep
+    //Returns None if the node budget ran out before the search was decided. On a win, self.path holds the moves
+    fn search_node(&mut self, game: &mut Game) -> Option<bool> {
+        let mut frames: Vec<Frame> = Vec::new();
+
+        match self.enter(game) {
+            Node::Won => return Some(true),
+            Node::Seen => return Some(false),
+            Node::OutOfBudget => return None,
+            Node::Expanded(moves) => frames.push(Frame { moves, next_move: 0, applied_move: None }),
+        }
+
+        while let Some(frame) = frames.last_mut() {
+            //Every move from here failed - step back out of this position
+            if frame.next_move == frame.moves.len() {
+                if let Some(applied_move) = &frame.applied_move {
+                    game.undo(applied_move);
+                    self.path.pop();
+                }
+                frames.pop();
+                continue
+            }
+
+            let game_move = frame.moves[frame.next_move].clone();
+            frame.next_move += 1;
+
+            let applied_move = apply(game, &game_move).expect("Generated moves are always legal");
+            self.path.push(game_move);
+
+            match self.enter(game) {
+                Node::Won => return Some(true),
+                Node::Seen => {
+                    game.undo(&applied_move);
+                    self.path.pop();
+                },
+                Node::OutOfBudget => return None,
+                Node::Expanded(moves) => frames.push(Frame { moves, next_move: 0, applied_move: Some(applied_move) }),
+            }
+        }
+
+        Some(false)
+    }
+
+    fn enter(&mut self, game: &Game) -> Node {
+        if game.aces.iter().all(|ace_stack| ace_stack.is_full()) {
+            return Node::Won;
+        }
+
+        //A position reached before is either still being explored further up the path, or was a dead end
+        if !self.table.insert(game) {
+            return Node::Seen;
+        }
+
+        if self.nodes >= self.max_nodes {
+            return Node::OutOfBudget;
+        }
+        self.nodes += 1;
+
+        //A safe foundation move is never worse than any other, so it's the only one tried
+        if let Some(safe_move) = safe_foundation_move(game) {
+            return Node::Expanded(vec![safe_move]);
+        }
+
+        let game_state = game.get_game_state();
+        let mut game_moves: Vec<GameMove> = match &self.weights {
+            Some(weights) => weights.rank(game, &game_state).into_iter().map(|(game_move, _)| game_move).collect(),
+            None => game_state.get_all_moves_youch().into_iter().cloned().collect(),
+        };
+        prune_dominated(game, &mut game_moves);
+        Node::Expanded(game_moves)
+    }
+}
+
+enum Node {
+    Won,
+    Seen,
+    OutOfBudget,
+    Expanded(Vec<GameMove>), //A new position, with its moves to try
+}
+
+struct Frame {
+    moves: Vec<GameMove>,
+    next_move: usize,
+    applied_move: Option<AppliedMove>, //The move that reached this position, None for the starting position
+}
@@ -0,0 +1,131 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use crate::solitare::solitare::Game;
+use crate::solitare::state::{CardPosition, GameMove, GameState};
+use crate::solver::greedy::{is_move_positive, is_stock_exhausted};
+use crate::solver::pair_cards::sort_by_pair_rules;
+use crate::solver::score::Weights;
+
+//Chooses between the moves of a position for a solver that never looks ahead. The greedy solver
+//plays whichever move comes first, and is stuck once a strategy leaves none. Search tries moves in
+//score order when the strategy is scored
+pub trait Strategy {
+    //The moves worth making, best first - moves left out aren't worth making at all.
+    //history is every move made to reach the game, oldest first
+    fn rank(&self, game: &Game, game_state: &GameState, history: &[GameMove]) -> Vec<GameMove>;
+}
+
+//The original greedy ordering: flips, then aces from the table and then the stock, kings into vacant
+//stacks, then whichever ace stack, table, stock and foundation moves is_move_positive allows, and
+//finally turning the stock over - until a whole pass of it plays nothing. Table and stock moves are
+//ordered among themselves by the pair card rules, and no card goes straight back to or from the aces
+pub struct GreedyStrategy;
+
+impl Strategy for GreedyStrategy {
+    fn rank(&self, game: &Game, game_state: &GameState, history: &[GameMove]) -> Vec<GameMove> {
+        let mut ranked = Vec::new();
+
+        ranked.extend(game_state.table_flip_moves.iter().cloned());
+        ranked.extend(game_state.table_ace_moves.iter().cloned());
+        ranked.extend(game_state.draw_ace_moves.iter().cloned());
+        ranked.extend(game_state.table_king_moves.iter().cloned());
+
+        let positive = |game_moves: &[GameMove]| -> Vec<GameMove> {
+            game_moves.iter().filter(|game_move| is_move_positive(game_move, game, game_state).0).cloned().collect()
+        };
+
+        ranked.extend(positive(&game_state.ace_stack_moves));
+        for game_moves in [&game_state.table_moves, &game_state.deck_moves] {
+            let mut onto_table = positive(game_moves);
+            sort_by_pair_rules(game, &mut onto_table);
+            ranked.extend(onto_table);
+        }
+        ranked.extend(positive(&game_state.foundation_moves));
+
+        if !is_stock_exhausted(game, history) {
+            ranked.extend(game_state.stock_moves.first().cloned());
+        }
+
+        //A card taken down and put straight back up, or the other way round, can both look positive - and repeat forever
+        ranked.retain(|game_move| {
+            let to_or_from_aces = matches!(game_move.from, CardPosition::Ace { .. }) || matches!(game_move.to, CardPosition::Ace { .. });
+            !(to_or_from_aces && reverses_last(game_move, history))
+        });
+
+        ranked
+    }
+}
+
+
+//Moves by score, best first, leaving out any that score nothing. Cards never move straight back, and
+//the stock is only turned until a whole pass of it plays nothing
+pub struct ScoredStrategy {
+    pub weights: Weights,
+}
+
+impl Strategy for ScoredStrategy {
+    fn rank(&self, game: &Game, game_state: &GameState, history: &[GameMove]) -> Vec<GameMove> {
+        let stock_exhausted = is_stock_exhausted(game, history);
+
+        self.weights.rank(game, game_state).into_iter()
+            .filter(|(game_move, score)| {
+                let turns_stock = game_move.from == CardPosition::Stock || game_move.to == CardPosition::Stock;
+                *score > 0 && if turns_stock { !stock_exhausted } else { !reverses_last(game_move, history) }
+            })
+            .map(|(game_move, _)| game_move)
+            .collect()
+    }
+}
+
+//Whether the move puts back the cards the last move of the history moved
+fn reverses_last(game_move: &GameMove, history: &[GameMove]) -> bool {
+    history.last().is_some_and(|last_move| last_move.from == game_move.to && last_move.to == game_move.from)
+}
+
+
+//Which strategy the greedy solver plays by - to compare them over a batch
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum StrategyKind {
+    #[default]
+    Greedy,
+    Scored(Weights),
+}
+
+impl StrategyKind {
+    pub fn strategy(self) -> Box<dyn Strategy> {
+        match self {
+            StrategyKind::Greedy => Box::new(GreedyStrategy),
+            StrategyKind::Scored(weights) => Box::new(ScoredStrategy { weights }),
+        }
+    }
+
+    //The weights search orders its moves by, when the strategy scores them
+    pub fn weights(self) -> Option<Weights> {
+        match self {
+            StrategyKind::Greedy => None,
+            StrategyKind::Scored(weights) => Some(weights),
+        }
+    }
+}
+
+impl Display for StrategyKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StrategyKind::Greedy => write!(f, "greedy"),
+            StrategyKind::Scored(_) => write!(f, "scored"),
+        }
+    }
+}
+
+//Scored starts from the default weights
+impl FromStr for StrategyKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "greedy" => Ok(StrategyKind::Greedy),
+            "scored" => Ok(StrategyKind::Scored(Weights::default())),
+            _ => Err(format!("unknown strategy '{}', expected greedy or scored", s)),
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use crate::solitare::card::Card;
+use crate::solitare::rules::{DrawMode, Redeals};
+use crate::solitare::solitare::{Game, TableStack};
+
+const DOWNTURNED_END: u8 = 0xFF;
+const STACK_END: u8 = 0xFE;
+
+//Canonical encoding of a Game - layouts that only differ by which column a stack sits in share a key,
+//as the same moves (up to column numbering) are available from both
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PositionKey(Box<[u8]>);
+
+impl PositionKey {
+    pub fn new(game: &Game) -> Self {
+        let mut key = Vec::with_capacity(52 + 7 * 2 + 4);
+
+        //Tableau - columns are interchangeable, so order them by content. Empty columns all encode the same
+        let mut stacks: Vec<Vec<u8>> = game.table.iter().map(encode_stack).collect();
+        stacks.sort_unstable();
+        for stack in stacks {
+            key.extend(stack);
+        }
+
+        //Foundation - the height is enough as each ace stack holds a single suit in order
+        for ace_stack in &game.aces {
+            key.push(ace_stack.ace_stack.len() as u8);
+        }
+
+        match game.rules.draw {
+            DrawMode::Open => {
+                //Stock - any stock card can be played at any time, so only its contents matter
+                let mut draw: Vec<u8> = game.draw.iter().map(card_byte).collect();
+                draw.sort_unstable();
+                key.extend(draw);
+            },
+            DrawMode::One | DrawMode::Three => {
+                //Stock and waste - the order decides which cards each draw turns up
+                key.extend(game.draw.iter().map(card_byte));
+                key.push(STACK_END);
+                key.extend(game.waste.iter().map(card_byte));
+                key.push(STACK_END);
+
+                //Redeals used only matters while they're limited
+                if let Redeals::Limited(_) = game.rules.redeals {
+                    key.push(game.redeals_used);
+                }
+            }
+        }
+
+        Self(key.into_boxed_slice())
+    }
+}
+
+fn card_byte(card: &Card) -> u8 {
+    card.index() as u8
+}
+
+fn encode_stack(table_stack: &TableStack) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(table_stack.downturned.len() + table_stack.upturned.len() + 2);
+
+    encoded.extend(table_stack.downturned.iter().map(card_byte));
+    encoded.push(DOWNTURNED_END);
+    encoded.extend(table_stack.upturned.iter().map(card_byte));
+    encoded.push(STACK_END);
+
+    encoded
+}
+
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TranspositionStats {
+    pub lookups: u64,
+    pub hits: u64, //Lookups that found the position already present, and so were pruned
+    pub entries: usize,
+}
+
+impl TranspositionStats {
+    pub fn hit_rate(&self) -> f64 {
+        if self.lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.lookups as f64
+        }
+    }
+}
+
+
+//Every position the search has entered
+#[derive(Default)]
+pub struct TranspositionTable {
+    seen: HashSet<PositionKey>,
+    dead: Arc<HashSet<PositionKey>>, //Positions other searches proved have no win, shared read only between threads
+    lookups: u64,
+    hits: u64,
+}
+
+impl TranspositionTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_dead(dead: Arc<HashSet<PositionKey>>) -> Self {
+        Self { dead, ..Self::default() }
+    }
+
+    //Records the position, returning false if it was already present or is known dead
+    pub fn insert(&mut self, game: &Game) -> bool {
+        self.lookups += 1;
+
+        let key = PositionKey::new(game);
+        let inserted = !self.dead.contains(&key) && self.seen.insert(key);
+        if !inserted {
+            self.hits += 1;
+        }
+
+        inserted
+    }
+
+    //Every position entered since the last clear - all proven dead if the search came back Lost,
+    //as they're all reachable from a position with no win
+    pub fn take_positions(&mut self) -> HashSet<PositionKey> {
+        std::mem::take(&mut self.seen)
+    }
+
+    //Forgets the positions entered, but not the dead positions shared in
+    pub fn clear(&mut self) {
+        self.seen.clear();
+        self.lookups = 0;
+        self.hits = 0;
+    }
+
+    pub fn stats(&self) -> TranspositionStats {
+        TranspositionStats {
+            lookups: self.lookups,
+            hits: self.hits,
+            entries: self.seen.len(),
+        }
+    }
+}
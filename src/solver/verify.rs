@@ -0,0 +1,53 @@
+use std::fmt::{Display, Formatter};
+use crate::solitare::apply::{apply, MoveError};
+use crate::solitare::solitare::Game;
+use crate::solitare::state::GameMove;
+
+//A solution that replayed legally to a won game
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Verified {
+    pub moves: usize,
+    //Steps (counting from 1) whose move was legal but not one get_game_state offered. Not wrong - move generation
+    //deliberately leaves some legal moves out - but a solution from our own solvers should never have any
+    pub ungenerated: Vec<usize>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VerifyError {
+    IllegalMove { step: usize, game_move: GameMove, error: MoveError }, //The first illegal move, counting steps from 1
+    NotWon { moves: usize }, //Every move was legal, but the ace stacks aren't full at the end
+}
+
+impl Display for VerifyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VerifyError::IllegalMove { step, game_move, error } => write!(f, "move {} ({}) is illegal: {}", step, game_move, error),
+            VerifyError::NotWon { moves } => write!(f, "all {} moves are legal but the game isn't won", moves),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+//Replays the moves on a copy of the game, checking each is legal when it's made and that they win the game
+pub fn verify(game: &Game, moves: &[GameMove]) -> Result<Verified, VerifyError> {
+    let mut game = *game;
+    let mut ungenerated = Vec::new();
+
+    for (move_index, game_move) in moves.iter().enumerate() {
+        let step = move_index + 1;
+        let is_generated = game.get_game_state().get_all_moves_youch().contains(&game_move);
+
+        apply(&mut game, game_move).map_err(|error| VerifyError::IllegalMove { step, game_move: game_move.clone(), error })?;
+
+        if !is_generated {
+            ungenerated.push(step);
+        }
+    }
+
+    if !game.aces.iter().all(|ace_stack| ace_stack.is_full()) {
+        return Err(VerifyError::NotWon { moves: moves.len() });
+    }
+
+    Ok(Verified { moves: moves.len(), ungenerated })
+}